@@ -0,0 +1,112 @@
+/// Integer constants that can be used in config files
+///
+/// Covers the constants used by the options documented in nextcloud's `config.sample.php`,
+/// the values match those of the php extensions as shipped by nextcloud's docker images.
+pub(crate) static CONFIG_CONSTANTS: &[(&str, i64)] = &[
+    ("PHP_INT_MAX", i64::MAX),
+    ("PHP_INT_MIN", i64::MIN),
+    ("PHP_INT_SIZE", 8),
+    ("E_ERROR", 1),
+    ("E_WARNING", 2),
+    ("E_PARSE", 4),
+    ("E_NOTICE", 8),
+    ("E_STRICT", 2048),
+    ("E_DEPRECATED", 8192),
+    ("E_ALL", 32767),
+    // log levels
+    (r"\OCP\ILogger::DEBUG", 0),
+    (r"\OCP\ILogger::INFO", 1),
+    (r"\OCP\ILogger::WARN", 2),
+    (r"\OCP\ILogger::ERROR", 3),
+    (r"\OCP\ILogger::FATAL", 4),
+    // database options
+    (r"\PDO::ATTR_TIMEOUT", 2),
+    (r"\PDO::ATTR_ERRMODE", 3),
+    (r"\PDO::ATTR_PERSISTENT", 12),
+    (r"\PDO::ATTR_EMULATE_PREPARES", 20),
+    (r"\PDO::ERRMODE_SILENT", 0),
+    (r"\PDO::ERRMODE_WARNING", 1),
+    (r"\PDO::ERRMODE_EXCEPTION", 2),
+    (r"\PDO::MYSQL_ATTR_USE_BUFFERED_QUERY", 1000),
+    (r"\PDO::MYSQL_ATTR_LOCAL_INFILE", 1001),
+    (r"\PDO::MYSQL_ATTR_INIT_COMMAND", 1002),
+    (r"\PDO::MYSQL_ATTR_COMPRESS", 1003),
+    (r"\PDO::MYSQL_ATTR_DIRECT_QUERY", 1004),
+    (r"\PDO::MYSQL_ATTR_FOUND_ROWS", 1005),
+    (r"\PDO::MYSQL_ATTR_IGNORE_SPACE", 1006),
+    (r"\PDO::MYSQL_ATTR_SSL_KEY", 1007),
+    (r"\PDO::MYSQL_ATTR_SSL_CERT", 1008),
+    (r"\PDO::MYSQL_ATTR_SSL_CA", 1009),
+    (r"\PDO::MYSQL_ATTR_SSL_CAPATH", 1010),
+    (r"\PDO::MYSQL_ATTR_SSL_CIPHER", 1011),
+    (r"\PDO::MYSQL_ATTR_SERVER_PUBLIC_KEY", 1012),
+    (r"\PDO::MYSQL_ATTR_MULTI_STATEMENTS", 1013),
+    (r"\PDO::MYSQL_ATTR_SSL_VERIFY_SERVER_CERT", 1014),
+    // redis options
+    (r"\Redis::OPT_SERIALIZER", 1),
+    (r"\Redis::OPT_PREFIX", 2),
+    (r"\Redis::OPT_READ_TIMEOUT", 3),
+    (r"\Redis::OPT_SCAN", 4),
+    (r"\Redis::OPT_TCP_KEEPALIVE", 6),
+    (r"\Redis::OPT_COMPRESSION", 7),
+    (r"\Redis::SERIALIZER_NONE", 0),
+    (r"\Redis::SERIALIZER_PHP", 1),
+    (r"\Redis::SERIALIZER_IGBINARY", 2),
+    (r"\Redis::SERIALIZER_MSGPACK", 3),
+    (r"\Redis::SERIALIZER_JSON", 4),
+    (r"\Redis::COMPRESSION_NONE", 0),
+    (r"\Redis::COMPRESSION_LZF", 1),
+    (r"\Redis::COMPRESSION_ZSTD", 2),
+    (r"\Redis::COMPRESSION_LZ4", 3),
+    (r"\RedisCluster::OPT_SERIALIZER", 1),
+    (r"\RedisCluster::OPT_PREFIX", 2),
+    (r"\RedisCluster::OPT_READ_TIMEOUT", 3),
+    (r"\RedisCluster::OPT_SLAVE_FAILOVER", 5),
+    (r"\RedisCluster::SERIALIZER_NONE", 0),
+    (r"\RedisCluster::SERIALIZER_PHP", 1),
+    (r"\RedisCluster::SERIALIZER_IGBINARY", 2),
+    (r"\RedisCluster::SERIALIZER_MSGPACK", 3),
+    (r"\RedisCluster::SERIALIZER_JSON", 4),
+    (r"\RedisCluster::FAILOVER_NONE", 0),
+    (r"\RedisCluster::FAILOVER_ERROR", 1),
+    (r"\RedisCluster::FAILOVER_DISTRIBUTE", 2),
+    (r"\RedisCluster::DISTRIBUTE", 2),
+    (r"\RedisCluster::FAILOVER_DISTRIBUTE_SLAVES", 3),
+    // memcached options
+    (r"\Memcached::OPT_NO_BLOCK", 0),
+    (r"\Memcached::OPT_TCP_NODELAY", 1),
+    (r"\Memcached::OPT_HASH", 2),
+    (r"\Memcached::OPT_POLL_TIMEOUT", 8),
+    (r"\Memcached::OPT_DISTRIBUTION", 9),
+    (r"\Memcached::OPT_BUFFER_WRITES", 10),
+    (r"\Memcached::OPT_CONNECT_TIMEOUT", 14),
+    (r"\Memcached::OPT_RETRY_TIMEOUT", 15),
+    (r"\Memcached::OPT_LIBKETAMA_COMPATIBLE", 16),
+    (r"\Memcached::OPT_BINARY_PROTOCOL", 18),
+    (r"\Memcached::OPT_SEND_TIMEOUT", 19),
+    (r"\Memcached::OPT_RECV_TIMEOUT", 20),
+    (r"\Memcached::OPT_SERVER_FAILURE_LIMIT", 21),
+    (r"\Memcached::OPT_TCP_KEEPALIVE", 32),
+    (r"\Memcached::OPT_REMOVE_FAILED_SERVERS", 35),
+    (r"\Memcached::OPT_DEAD_TIMEOUT", 36),
+    (r"\Memcached::OPT_COMPRESSION", -1001),
+    (r"\Memcached::OPT_PREFIX_KEY", -1002),
+    (r"\Memcached::OPT_SERIALIZER", -1003),
+    (r"\Memcached::OPT_COMPRESSION_TYPE", -1004),
+    (r"\Memcached::SERIALIZER_PHP", 1),
+    (r"\Memcached::SERIALIZER_IGBINARY", 2),
+    (r"\Memcached::SERIALIZER_JSON", 3),
+    (r"\Memcached::SERIALIZER_JSON_ARRAY", 4),
+    (r"\Memcached::SERIALIZER_MSGPACK", 5),
+    (r"\Memcached::HASH_DEFAULT", 0),
+    (r"\Memcached::HASH_MD5", 1),
+    (r"\Memcached::HASH_CRC", 2),
+    (r"\Memcached::HASH_FNV1_64", 3),
+    (r"\Memcached::HASH_FNV1A_64", 4),
+    (r"\Memcached::HASH_FNV1_32", 5),
+    (r"\Memcached::HASH_FNV1A_32", 6),
+    (r"\Memcached::HASH_HSIEH", 7),
+    (r"\Memcached::HASH_MURMUR", 8),
+    (r"\Memcached::DISTRIBUTION_MODULA", 0),
+    (r"\Memcached::DISTRIBUTION_CONSISTENT", 1),
+];
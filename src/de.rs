@@ -0,0 +1,364 @@
+use crate::ValueSource;
+use php_literal_parser::{Key, Value};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::fmt::{Display, Formatter};
+
+/// Error while deserializing a config value
+#[derive(Debug, Clone)]
+pub struct DeserializeError {
+    message: String,
+    key: Option<String>,
+    defined_in: Option<ValueSource>,
+}
+
+impl DeserializeError {
+    /// Path of the config value that failed to deserialize, like `myapp.servers.0.port`
+    pub fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or_default()
+    }
+
+    /// Where the top-level config key containing the value is defined
+    pub fn defined_in(&self) -> Option<&ValueSource> {
+        self.defined_in.as_ref()
+    }
+
+    pub(crate) fn with_source(mut self, source: Option<&ValueSource>) -> Self {
+        self.defined_in = source.cloned();
+        self
+    }
+
+    fn at(mut self, path: &str) -> Self {
+        if self.key.is_none() && !path.is_empty() {
+            self.key = Some(path.into());
+        }
+        self
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "invalid value for `{}`: {}", key, self.message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        if let Some(source) = &self.defined_in {
+            write!(f, " (defined in {})", source)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        DeserializeError {
+            message: msg.to_string(),
+            key: None,
+            defined_in: None,
+        }
+    }
+}
+
+type Result<T, E = DeserializeError> = std::result::Result<T, E>;
+
+/// Serde deserializer for a parsed config value
+///
+/// Errors contain the path of the value that failed to deserialize.
+pub struct ValueDeserializer<'de> {
+    value: &'de Value,
+    path: String,
+}
+
+impl<'de> ValueDeserializer<'de> {
+    pub fn new(value: &'de Value) -> Self {
+        ValueDeserializer {
+            value,
+            path: String::new(),
+        }
+    }
+
+    pub(crate) fn with_path(value: &'de Value, path: String) -> Self {
+        ValueDeserializer { value, path }
+    }
+
+    fn child(&self, key: &Key, value: &'de Value) -> Self {
+        let path = if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.path, key)
+        };
+        ValueDeserializer { value, path }
+    }
+}
+
+/// Get the items of an array ordered by key
+fn sorted_items(map: &std::collections::HashMap<Key, Value>) -> Vec<(&Key, &Value)> {
+    let mut items = map.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(key, _)| *key);
+    items
+}
+
+/// Check if an array is a list with keys `0..n`
+fn is_list(items: &[(&Key, &Value)]) -> bool {
+    items
+        .iter()
+        .enumerate()
+        .all(|(i, (key, _))| key.as_int() == Some(i as i64))
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let result = match self.value {
+            Value::Bool(bool) => visitor.visit_bool(*bool),
+            Value::Int(int) => visitor.visit_i64(*int),
+            Value::Float(float) => visitor.visit_f64(*float),
+            Value::String(str) => visitor.visit_borrowed_str(str),
+            Value::Null => visitor.visit_unit(),
+            Value::Array(map) => {
+                let items = sorted_items(map);
+                if is_list(&items) {
+                    visitor.visit_seq(SeqDeserializer::new(&self, items))
+                } else {
+                    visitor.visit_map(MapDeserializer::new(&self, items))
+                }
+            }
+        };
+        result.map_err(|err| err.at(&self.path))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Array(map) => visitor
+                .visit_seq(SeqDeserializer::new(&self, sorted_items(map)))
+                .map_err(|err| err.at(&self.path)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Value::Array(map) => visitor
+                .visit_map(MapDeserializer::new(&self, sorted_items(map)))
+                .map_err(|err| err.at(&self.path)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let result = match self.value {
+            Value::String(variant) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            Value::Array(map) if map.len() == 1 => {
+                let (key, value) = map.iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    variant: key,
+                    value: self.child(key, value),
+                })
+            }
+            _ => return self.deserialize_any(visitor),
+        };
+        result.map_err(|err| err.at(&self.path))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct identifier
+    }
+}
+
+struct SeqDeserializer<'a, 'de> {
+    parent: &'a ValueDeserializer<'de>,
+    items: std::vec::IntoIter<(&'de Key, &'de Value)>,
+}
+
+impl<'a, 'de> SeqDeserializer<'a, 'de> {
+    fn new(parent: &'a ValueDeserializer<'de>, items: Vec<(&'de Key, &'de Value)>) -> Self {
+        SeqDeserializer {
+            parent,
+            items: items.into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.items.next() {
+            Some((key, value)) => seed.deserialize(self.parent.child(key, value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+struct MapDeserializer<'a, 'de> {
+    parent: &'a ValueDeserializer<'de>,
+    items: std::vec::IntoIter<(&'de Key, &'de Value)>,
+    value: Option<(&'de Key, &'de Value)>,
+}
+
+impl<'a, 'de> MapDeserializer<'a, 'de> {
+    fn new(parent: &'a ValueDeserializer<'de>, items: Vec<(&'de Key, &'de Value)>) -> Self {
+        MapDeserializer {
+            parent,
+            items: items.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.items.next() {
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(KeyDeserializer(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let (key, value) = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(self.parent.child(key, value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Array keys can be deserialized as either integer or string
+struct KeyDeserializer<'de>(&'de Key);
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Key::Int(int) => visitor.visit_i64(*int),
+            Key::String(str) => visitor.visit_borrowed_str(str),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Key::Int(int) => visitor.visit_string(int.to_string()),
+            Key::String(str) => visitor.visit_borrowed_str(str),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum ignored_any
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: &'de Key,
+    value: ValueDeserializer<'de>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = DeserializeError;
+    type Variant = ValueDeserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(KeyDeserializer(self.variant))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> VariantAccess<'de> for ValueDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
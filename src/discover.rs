@@ -0,0 +1,134 @@
+use crate::php::Context;
+use crate::source::ConfigSource;
+use std::path::{Path, PathBuf};
+
+/// Nextcloud's snap package keeps its config under `$SNAP_DATA`
+const SNAP_CONFIG_DIR: &str = "/var/snap/nextcloud/current/nextcloud/config";
+const SNAP_SERVER_ROOT: &str = "/snap/nextcloud/current/htdocs";
+
+/// Docker volume names used by nextcloud all-in-one
+const AIO_VOLUME: &str = "nextcloud_aio_nextcloud";
+const AIO_DATA_VOLUME: &str = "nextcloud_aio_nextcloud_data";
+
+/// How a nextcloud installation is laid out on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// The config directory is set by the `NEXTCLOUD_CONFIG_DIR` environment variable
+    ConfigDirEnv,
+    /// The config is stored in `config/` inside the installation directory
+    Standard,
+    /// Installed from the snap package, with the config stored in the snap's data directory
+    Snap,
+    /// The installation directory is stored in a docker volume
+    DockerVolume,
+    /// Installed with nextcloud all-in-one
+    Aio,
+}
+
+/// The location of a nextcloud installation's config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installation {
+    pub layout: Layout,
+    /// The directory containing the config files
+    pub config_dir: PathBuf,
+    /// The main `config.php`, to be passed to [`parse_glob`](crate::parse_glob)
+    pub config_file: PathBuf,
+    /// The nextcloud installation directory, if known
+    pub server_root: Option<PathBuf>,
+}
+
+impl Installation {
+    fn new(layout: Layout, config_dir: PathBuf, server_root: Option<PathBuf>) -> Self {
+        Installation {
+            layout,
+            config_file: config_dir.join("config.php"),
+            config_dir,
+            server_root,
+        }
+    }
+}
+
+/// Find the config directory for the installation at `dir`
+///
+/// `dir` can be the installation directory, the config or data directory, a docker volume
+/// containing the installation or any directory of a snap or all-in-one installation.
+pub(crate) fn find_installation(
+    dir: &Path,
+    source: &dyn ConfigSource,
+    ctx: Context,
+) -> Option<Installation> {
+    candidates(dir, ctx)
+        .into_iter()
+        .find(|installation| has_config(source, &installation.config_dir))
+}
+
+fn candidates(dir: &Path, ctx: Context) -> Vec<Installation> {
+    let mut candidates = Vec::new();
+    let parent = dir.parent().map(Path::to_path_buf);
+
+    if let Some(config_dir) = ctx.getenv("NEXTCLOUD_CONFIG_DIR") {
+        candidates.push(Installation::new(
+            Layout::ConfigDirEnv,
+            config_dir.into(),
+            Some(dir.into()),
+        ));
+    }
+
+    candidates.push(Installation::new(
+        Layout::Standard,
+        dir.join("config"),
+        Some(dir.into()),
+    ));
+    // pointed at the config dir itself
+    candidates.push(Installation::new(
+        Layout::Standard,
+        dir.into(),
+        parent.clone(),
+    ));
+
+    if dir.starts_with("/snap/nextcloud") || dir.starts_with("/var/snap/nextcloud") {
+        candidates.push(Installation::new(
+            Layout::Snap,
+            SNAP_CONFIG_DIR.into(),
+            Some(SNAP_SERVER_ROOT.into()),
+        ));
+    }
+
+    let aio_volumes = dir.ancestors().find_map(|ancestor| {
+        let name = ancestor.file_name()?;
+        (name == AIO_VOLUME || name == AIO_DATA_VOLUME).then(|| ancestor.parent())?
+    });
+    if let Some(volumes) = aio_volumes {
+        let server_root = volumes.join(AIO_VOLUME).join("_data");
+        candidates.push(Installation::new(
+            Layout::Aio,
+            server_root.join("config"),
+            Some(server_root),
+        ));
+    }
+
+    let volume_data = dir.join("_data");
+    candidates.push(Installation::new(
+        Layout::DockerVolume,
+        volume_data.join("config"),
+        Some(volume_data),
+    ));
+
+    // pointed at a data directory inside the installation directory
+    if let Some(parent) = parent {
+        candidates.push(Installation::new(
+            Layout::Standard,
+            parent.join("config"),
+            Some(parent),
+        ));
+    }
+
+    candidates
+}
+
+fn has_config(source: &dyn ConfigSource, config_dir: &Path) -> bool {
+    let config_file = config_dir.join("config.php");
+    source
+        .list_files(config_dir)
+        .is_ok_and(|files| files.contains(&config_file))
+}
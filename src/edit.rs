@@ -0,0 +1,323 @@
+use crate::nc::glob_config_files;
+use crate::php::{self, ArraySpan, Context, ItemSpans};
+use crate::serialize::{item_to_php, string_to_php, value_to_php};
+use crate::{Config, ConfigParser, Error, PhpParseError, Result};
+use php_literal_parser::{Key, Value};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Change config values while keeping the formatting and comments of the config files
+///
+/// Existing keys are changed in the file that defines their effective value, new keys are added
+/// to the main config file. Nothing is written to disk until [`save`](Self::save) is called.
+#[derive(Debug)]
+pub struct ConfigEditor {
+    parser: ConfigParser,
+    server_root: Option<PathBuf>,
+    /// The config files in the order they are merged
+    files: Vec<EditedFile>,
+}
+
+#[derive(Debug, Clone)]
+struct EditedFile {
+    path: PathBuf,
+    content: String,
+    modified: bool,
+    /// Where the items of `$CONFIG` are defined
+    spans: ItemSpans,
+    /// The array literal assigned to `$CONFIG`
+    array_span: Option<ArraySpan>,
+}
+
+impl ConfigEditor {
+    fn new(parser: ConfigParser, path: &Path) -> Result<Self> {
+        let mut editor = ConfigEditor {
+            server_root: parser.server_root_for(Some(path)),
+            parser,
+            files: Vec::new(),
+        };
+        for path in glob_config_files(path, editor.parser.source()) {
+            let content = editor
+                .parser
+                .source()
+                .read_file(&path)
+                .map_err(|err| Error::ReadFailed(err, path.clone()))?;
+            let file = editor.parse(path, content)?;
+            editor.files.push(file);
+        }
+        Ok(editor)
+    }
+
+    fn parse(&self, path: PathBuf, content: String) -> Result<EditedFile> {
+        let file = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+        let ctx = Context {
+            server_root: self.server_root.as_deref(),
+            file: Some(&file),
+            ..self.parser.context()
+        };
+        let mut output = php::execute(&content, ctx).map_err(|err| {
+            Error::Php(PhpParseError {
+                err,
+                path: path.clone(),
+            })
+        })?;
+        Ok(EditedFile {
+            spans: output.item_spans.remove("CONFIG").unwrap_or_default(),
+            array_span: output.array_spans.remove("CONFIG"),
+            path,
+            content,
+            modified: false,
+        })
+    }
+
+    /// Replace the content of a file, failing if the new content can't be parsed
+    fn update(&mut self, index: usize, content: String) -> Result<()> {
+        let mut file = self.parse(self.files[index].path.clone(), content)?;
+        file.modified = true;
+        self.files[index] = file;
+        Ok(())
+    }
+
+    /// The last file that defines a key, which is the one providing the effective value
+    fn defining_file(&self, key: &Key) -> Option<usize> {
+        self.files
+            .iter()
+            .rposition(|file| file.spans.contains_key(key))
+    }
+
+    /// Set a top-level config key
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) -> Result<()> {
+        let value = value.into();
+        let php_key = php::to_key(&Value::String(key.into()));
+        let Some(index) = self.defining_file(&php_key) else {
+            return self.insert(key, &value);
+        };
+
+        let file = &self.files[index];
+        let span = &file.spans[&php_key];
+        let indent = line_indent(&file.content, span.item.start);
+        let mut content = file.content.clone();
+        match &span.value {
+            Some(value_span) => {
+                content.replace_range(value_span.clone(), &value_to_php(key, &value, indent));
+            }
+            None => {
+                // the key is only defined by assignments to nested items,
+                // assign the whole item after the last one
+                let end = statement_end(&content, span.item.end);
+                let statement = format!(
+                    "\n{:indent$}$CONFIG[{}] = {};",
+                    "",
+                    string_to_php(key),
+                    value_to_php(key, &value, indent)
+                );
+                content.insert_str(end, &statement);
+            }
+        }
+        self.update(index, content)
+    }
+
+    /// Add a new key to the `$CONFIG` array of the main config file
+    fn insert(&mut self, key: &str, value: &Value) -> Result<()> {
+        let file = &self.files[0];
+        let ArraySpan {
+            array: array_span,
+            items,
+        } = file.array_span.clone().ok_or_else(|| {
+            Error::NotEditable(
+                file.path.clone(),
+                "it doesn't assign an array literal to $CONFIG",
+            )
+        })?;
+        let content = &file.content;
+        let close = array_span.end - 1;
+        // `spans` points to the last assignment of each key, which can be after the literal
+        let last_item = items.last().cloned();
+        let close_line_start = line_start(content, close);
+        let indent = match &last_item {
+            Some(item) => line_indent(content, item.start),
+            None => line_indent(content, close) + 2,
+        };
+
+        let mut new_content = content.clone();
+        if close_line_start > array_span.start && content[close_line_start..close].trim().is_empty()
+        {
+            let item = format!("{:indent$}{},\n", "", item_to_php(key, value, indent));
+            new_content.insert_str(close_line_start, &item);
+        } else {
+            let separator = if last_item.is_some() { " " } else { "" };
+            let item = format!("{separator}{}", item_to_php(key, value, indent));
+            new_content.insert_str(close, &item);
+        }
+        if let Some(item) = last_item {
+            if !content[item.end..].trim_start().starts_with(',') {
+                new_content.insert(item.end, ',');
+            }
+        }
+        self.update(0, new_content)
+    }
+
+    /// Remove a top-level config key from all files that define it
+    ///
+    /// Returns `false` if the key wasn't set. Fails without changing anything if one of the
+    /// assignments is the body of an `if` without braces, since removing it would make the `if`
+    /// apply to the next statement.
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        let original = self.files.clone();
+        let result = self.remove_key(key);
+        if result.is_err() {
+            self.files = original;
+        }
+        result
+    }
+
+    fn remove_key(&mut self, key: &str) -> Result<bool> {
+        let php_key = php::to_key(&Value::String(key.into()));
+        let mut removed = false;
+        // a key can be defined by multiple files, or multiple times in the same file
+        while let Some(index) = self.defining_file(&php_key) {
+            let file = &self.files[index];
+            let span = &file.spans[&php_key];
+            if !span.removable {
+                return Err(Error::NotEditable(
+                    file.path.clone(),
+                    "the key is assigned as the body of an `if` without braces",
+                ));
+            }
+            let range = removal_range(&file.content, span.item.clone());
+            let mut content = file.content.clone();
+            content.replace_range(range, "");
+            self.update(index, content)?;
+            removed = true;
+        }
+        Ok(removed)
+    }
+
+    /// Parse the edited config
+    pub fn config(&self) -> Result<Config> {
+        let files = self
+            .files
+            .iter()
+            .map(|file| (file.path.clone(), file.content.clone()))
+            .collect::<HashMap<_, _>>();
+        self.parser
+            .clone()
+            .with_source(files)
+            .parse_glob(&self.files[0].path)
+    }
+
+    /// Write the changed files to disk
+    ///
+    /// Each file is written to a temporary file first, which then replaces the original file.
+    /// Files are always written to the local filesystem, even if they were loaded from a custom
+    /// [`ConfigSource`](crate::ConfigSource).
+    pub fn save(&mut self) -> Result<()> {
+        for file in self.files.iter_mut().filter(|file| file.modified) {
+            write_atomic(&file.path, &file.content)
+                .map_err(|err| Error::WriteFailed(err, file.path.clone()))?;
+            file.modified = false;
+        }
+        Ok(())
+    }
+}
+
+fn line_start(content: &str, pos: usize) -> usize {
+    content[..pos].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+fn line_indent(content: &str, pos: usize) -> usize {
+    let start = line_start(content, pos);
+    content[start..pos].len() - content[start..pos].trim_start().len()
+}
+
+/// The end of the statement containing `pos`, after the `;`
+fn statement_end(content: &str, pos: usize) -> usize {
+    let rest = &content[pos..];
+    let trimmed = rest.trim_start();
+    match trimmed.strip_prefix(';') {
+        Some(_) => pos + rest.len() - trimmed.len() + 1,
+        None => pos,
+    }
+}
+
+/// The range to remove to delete an item, including the separator after it
+///
+/// If the item is on a line of its own, the whole line is removed.
+fn removal_range(content: &str, item: Range<usize>) -> Range<usize> {
+    let is_space = |c: char| c == ' ' || c == '\t';
+    let mut end = item.end;
+    end += content[end..].len() - content[end..].trim_start_matches(is_space).len();
+    if content[end..].starts_with([',', ';']) {
+        end += 1;
+    }
+    end += content[end..].len() - content[end..].trim_start_matches(is_space).len();
+
+    let start = line_start(content, item.start);
+    let newline = ["\n", "\r\n"]
+        .into_iter()
+        .find(|newline| content[end..].starts_with(newline));
+    match newline {
+        Some(newline) if content[start..item.start].trim().is_empty() => start..end + newline.len(),
+        _ => item.start..end,
+    }
+}
+
+fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+    // hidden files aren't loaded as additional config files
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(name);
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut file = File::create(&tmp_path)?;
+        // keep the permissions and owner of the original file, which usually belongs to the web server
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+            #[cfg(unix)]
+            copy_owner(&file, &metadata)?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Give the new file the owner of the file it replaces
+///
+/// Users that can write to a file they don't own, like an admin in the web server's group,
+/// can't change the owner. The new file is owned by them instead.
+#[cfg(unix)]
+fn copy_owner(file: &File, original: &fs::Metadata) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let current = file.metadata()?;
+    if current.uid() == original.uid() && current.gid() == original.gid() {
+        return Ok(());
+    }
+    match std::os::unix::fs::fchown(file, Some(original.uid()), Some(original.gid())) {
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => Ok(()),
+        result => result,
+    }
+}
+
+impl ConfigParser {
+    /// Load a config file and all `*.config.php` files next to it for editing
+    pub fn edit(&self, path: impl AsRef<Path>) -> Result<ConfigEditor> {
+        ConfigEditor::new(self.clone(), path.as_ref())
+    }
+}
+
+pub fn edit(path: impl AsRef<Path>) -> Result<ConfigEditor> {
+    ConfigParser::new().edit(path)
+}
@@ -1,21 +1,393 @@
+mod constants;
+mod de;
+mod discover;
+mod edit;
 mod nc;
+#[cfg(feature = "tokio")]
+mod nonblocking;
+mod php;
+#[cfg(feature = "redis")]
+mod redis_client;
+mod serialize;
+mod source;
+mod version;
+#[cfg(feature = "watch")]
+mod watch;
 
 use form_urlencoded::Serializer;
 use itertools::Either;
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
 use std::iter::once;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error;
 
-pub use nc::{parse, parse_glob};
+pub use de::{DeserializeError, ValueDeserializer};
+pub use discover::{Installation, Layout};
+pub use edit::{edit, ConfigEditor};
+pub use nc::{
+    discover, parse, parse_glob, parse_instance, parse_reader, parse_str, parse_version,
+    ConfigParser,
+};
+#[cfg(feature = "tokio")]
+pub use nonblocking::{parse_async, parse_glob_async};
+pub use php_literal_parser::{Key, Value};
+pub use serialize::to_php;
+pub use source::{ConfigSource, FileSystemSource};
+pub use version::{Version, VersionInfo};
+#[cfg(feature = "watch")]
+pub use watch::{ConfigWatcher, WatchEvent};
 
 #[derive(Debug)]
 pub struct Config {
     pub database: Database,
     pub database_prefix: String,
     pub redis: RedisConfig,
+    pub memcache: MemcacheConfig,
+    pub memcached: MemcachedConfig,
     pub nextcloud_url: String,
+    /// Config keys that have been overridden by `NC_<key>` environment variables
+    pub env_overrides: Vec<String>,
+    /// Config keys that are set by more than one config file
+    pub duplicate_keys: Vec<DuplicateKey>,
+    /// Where the effective value for each top-level config key is defined
+    pub sources: HashMap<String, ValueSource>,
+    /// The full merged config, including environment overrides
+    pub raw: Value,
+}
+
+impl Config {
+    /// Get where the effective value for a top-level config key is defined
+    pub fn source_of(&self, key: &str) -> Option<&ValueSource> {
+        self.sources.get(key)
+    }
+
+    /// Get a config value by key or by dotted path into nested arrays, like `redis.cluster.seeds`
+    ///
+    /// Keys that contain dots themselves, like `overwrite.cli.url`, can be used as-is.
+    /// Like nextcloud, values set to `null` are treated as not being set.
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        lookup(&self.raw, path).filter(|value| !value.is_null())
+    }
+
+    /// Get a config value as string, like nextcloud's `getSystemValueString`
+    ///
+    /// Returns `None` if the value isn't set or is an array.
+    pub fn get_str(&self, path: &str) -> Option<String> {
+        self.get(path)
+            .filter(|value| !value.is_array())
+            .map(php::to_string)
+    }
+
+    /// Get a config value as bool, like nextcloud's `getSystemValueBool`
+    pub fn get_bool(&self, path: &str) -> Option<bool> {
+        self.get(path).map(php::is_truthy)
+    }
+
+    /// Get a config value as integer, like nextcloud's `getSystemValueInt`
+    pub fn get_int(&self, path: &str) -> Option<i64> {
+        self.get(path).map(php::to_int)
+    }
+
+    /// Get the items of an array config value as strings, ordered by their keys
+    ///
+    /// Returns `None` if the value isn't set, isn't an array or contains nested arrays.
+    pub fn get_list(&self, path: &str) -> Option<Vec<String>> {
+        let Value::Array(map) = self.get(path)? else {
+            return None;
+        };
+        let mut items = map.iter().collect::<Vec<_>>();
+        items.sort_by_key(|(key, _)| *key);
+        items
+            .into_iter()
+            .map(|(_, value)| (!value.is_array()).then(|| php::to_string(value)))
+            .collect()
+    }
+
+    /// Get whether the installation has been completed
+    pub fn installed(&self) -> bool {
+        self.get_bool("installed").unwrap_or(false)
+    }
+
+    /// Get whether maintenance mode is enabled
+    pub fn maintenance(&self) -> bool {
+        self.get_bool("maintenance").unwrap_or(false)
+    }
+
+    /// Get the hour (in UTC) at which the daily maintenance window starts, if configured
+    pub fn maintenance_window_start(&self) -> Option<u8> {
+        self.get_int("maintenance_window_start")
+            .and_then(|hour| u8::try_from(hour).ok())
+            .filter(|hour| *hour < 24)
+    }
+
+    /// Get the version of nextcloud the installation was last upgraded to
+    pub fn installed_version(&self) -> Option<Version> {
+        self.get_str("version")?.parse().ok()
+    }
+
+    /// Get the parts of the config that differ from an older version of the config
+    ///
+    /// Changes to the database, redis or url config are reported as a single change for the section,
+    /// changes to any other top-level key are reported by key.
+    pub fn changes_since(&self, old: &Config) -> Vec<ConfigChange> {
+        let (Value::Array(new_values), Value::Array(old_values)) = (&self.raw, &old.raw) else {
+            return Vec::new();
+        };
+        let mut changed_keys = new_values
+            .keys()
+            .chain(old_values.keys())
+            .filter(|key| new_values.get(*key) != old_values.get(*key))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        changed_keys.sort();
+        changed_keys.dedup();
+
+        let mut changes = Vec::new();
+        for key in changed_keys {
+            let change = match key.as_str() {
+                "datadirectory" if matches!(self.database, Database::Sqlite { .. }) => {
+                    ConfigChange::Database
+                }
+                "redis" | "redis.cluster" => ConfigChange::Redis,
+                "overwrite.cli.url" => ConfigChange::NextcloudUrl,
+                key if key.starts_with("db") || key.starts_with("mysql.") => ConfigChange::Database,
+                _ => ConfigChange::Key(key),
+            };
+            if !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+        changes.sort();
+        changes
+    }
+
+    /// Write the merged config as a single php config file
+    ///
+    /// Values overridden by environment variables are written with their overridden value.
+    pub fn to_php(&self) -> String {
+        to_php(&self.raw)
+    }
+
+    /// Deserialize a config value into a custom type, like the config for an app stored under its own key
+    ///
+    /// If the key isn't set, the section is deserialized from `null`.
+    pub fn section<'de, T: Deserialize<'de>>(&'de self, path: &str) -> Result<T, DeserializeError> {
+        let value = lookup(&self.raw, path).unwrap_or(&Value::Null);
+        let source = self
+            .sources
+            .iter()
+            .filter(|(key, _)| {
+                path.strip_prefix(key.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, source)| source);
+        T::deserialize(ValueDeserializer::with_path(value, path.into()))
+            .map_err(|err| err.with_source(source))
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let Value::Array(map) = value else {
+        return None;
+    };
+    let get = |key: &str| map.get(&php::to_key(&Value::String(key.into())));
+    get(path).or_else(|| {
+        path.match_indices('.')
+            .find_map(|(i, _)| lookup(get(&path[..i])?, &path[i + 1..]))
+    })
+}
+
+/// A nextcloud installation with its parsed config and version
+#[derive(Debug)]
+pub struct Instance {
+    pub config: Config,
+    pub version: VersionInfo,
+    pub installation: Installation,
+}
+
+impl Instance {
+    /// Get whether the instance can be used or is installing, upgrading or in maintenance
+    pub fn state(&self) -> InstanceState {
+        if !self.config.installed() {
+            return InstanceState::NotInstalled;
+        }
+        if self.config.maintenance() {
+            return InstanceState::Maintenance;
+        }
+        match self.config.installed_version() {
+            Some(installed) if installed < self.version.version => InstanceState::UpgradePending {
+                installed,
+                available: self.version.version.clone(),
+            },
+            _ => InstanceState::Ready,
+        }
+    }
+}
+
+/// Whether an instance is ready to be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceState {
+    /// The installation hasn't been completed yet
+    NotInstalled,
+    /// Maintenance mode is enabled, this includes while an upgrade is being performed
+    Maintenance,
+    /// The code has been updated but the upgrade hasn't been run yet
+    UpgradePending {
+        /// The version from the config
+        installed: Version,
+        /// The version from `version.php`
+        available: Version,
+    },
+    Ready,
+}
+
+/// The parts of a config that could be parsed, see [`ConfigParser::parse_lenient`]
+#[derive(Debug)]
+pub struct PartialConfig {
+    pub database: Result<Database>,
+    pub database_prefix: String,
+    pub redis: RedisConfig,
+    pub memcache: MemcacheConfig,
+    pub memcached: MemcachedConfig,
+    pub nextcloud_url: Result<String>,
+    /// Config keys that have been overridden by `NC_<key>` environment variables
+    pub env_overrides: Vec<String>,
+    /// Config keys that are set by more than one config file
+    pub duplicate_keys: Vec<DuplicateKey>,
+    /// Where the effective value for each top-level config key is defined
+    pub sources: HashMap<String, ValueSource>,
+    /// The full merged config, including environment overrides
+    pub raw: Value,
+    /// Problems that didn't prevent the config from being parsed
+    pub warnings: Vec<ConfigWarning>,
+}
+
+/// A problem in the config that was ignored while parsing
+#[derive(Debug, Error)]
+pub enum ConfigWarning {
+    #[error("skipped config file {}: {error}", path.display())]
+    SkippedFile { path: PathBuf, error: Error },
+    #[error("ignored value for `{key}`: {reason}")]
+    IgnoredValue { key: String, reason: &'static str },
+}
+
+/// A part of the config that changed, see [`Config::changes_since`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigChange {
+    Database,
+    Redis,
+    NextcloudUrl,
+    /// Any other top-level config key
+    Key(String),
+}
+
+/// Where a config value is defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    File {
+        path: PathBuf,
+        /// Byte range of the `key => value` item or `$CONFIG[key] = value` assignment
+        span: Range<usize>,
+        /// Line number of the start of the span, starting at 1
+        line: usize,
+    },
+    /// Set by a `NC_<key>` environment variable
+    Env { name: String },
+}
+
+impl Display for ValueSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueSource::File { path, line, .. } => write!(f, "{}:{}", path.display(), line),
+            ValueSource::Env { name } => write!(f, "environment variable {}", name),
+        }
+    }
+}
+
+/// A config key that is set by more than one config file
+#[derive(Debug, Clone)]
+pub struct DuplicateKey {
+    pub key: String,
+    /// The files setting the key, in the order they are merged
+    pub defined_in: Vec<PathBuf>,
+}
+
+impl DuplicateKey {
+    /// The file whose value ends up in the merged config
+    pub fn effective_file(&self) -> &Path {
+        self.defined_in
+            .last()
+            .expect("duplicate keys are defined in multiple files")
+    }
+}
+
+/// The caches configured by `memcache.local`, `memcache.distributed` and `memcache.locking`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemcacheConfig {
+    pub local: Option<MemcacheBackend>,
+    /// Falls back to the local cache if `memcache.distributed` isn't set, like nextcloud does
+    pub distributed: Option<MemcacheBackend>,
+    pub locking: Option<MemcacheBackend>,
+}
+
+impl MemcacheConfig {
+    /// Get what redis is used for, or `None` if none of the caches use redis
+    pub fn redis_usage(&self) -> Option<RedisUsage> {
+        let is_redis = |backend: &Option<MemcacheBackend>| backend == &Some(MemcacheBackend::Redis);
+        let usage = RedisUsage {
+            local: is_redis(&self.local),
+            distributed: is_redis(&self.distributed),
+            locking: is_redis(&self.locking),
+        };
+        (usage.local || usage.distributed || usage.locking).then_some(usage)
+    }
+}
+
+/// A memcache class that can be configured in nextcloud
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemcacheBackend {
+    /// `\OC\Memcache\Redis`
+    Redis,
+    /// `\OC\Memcache\APCu`
+    APCu,
+    /// `\OC\Memcache\Memcached`
+    Memcached,
+    /// `\OC\Memcache\ArrayCache`
+    ArrayCache,
+}
+
+impl FromStr for MemcacheBackend {
+    type Err = ();
+
+    /// Parse a memcache class name, like php the leading `\` is optional and the name is case-insensitive
+    fn from_str(class: &str) -> Result<Self, Self::Err> {
+        let class = class.trim_start_matches('\\');
+        [
+            (r"OC\Memcache\Redis", MemcacheBackend::Redis),
+            (r"OC\Memcache\APCu", MemcacheBackend::APCu),
+            (r"OC\Memcache\Memcached", MemcacheBackend::Memcached),
+            (r"OC\Memcache\ArrayCache", MemcacheBackend::ArrayCache),
+        ]
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(class))
+        .map(|(_, backend)| backend)
+        .ok_or(())
+    }
+}
+
+/// Which caches use redis, see [`MemcacheConfig::redis_usage`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedisUsage {
+    pub local: bool,
+    pub distributed: bool,
+    pub locking: bool,
 }
 
 #[derive(Debug)]
@@ -41,28 +413,89 @@ pub enum RedisConnectionAddr {
 
 impl RedisConnectionAddr {
     fn parse(mut host: &str, port: Option<u16>, tls: bool) -> Self {
-        if host.starts_with("/") {
-            RedisConnectionAddr::Unix { path: host.into() }
+        let tls = if host.starts_with("tls://") || host.starts_with("rediss://") {
+            host = host.split_once("://").unwrap().1;
+            true
         } else {
-            let tls = if host.starts_with("tls://") || host.starts_with("rediss://") {
-                host = host.split_once("://").unwrap().1;
-                true
-            } else {
-                tls
-            };
-            if host == "localhost" {
-                host = "127.0.0.1";
-            }
-            let (host, port, _) = if let Some(port) = port {
-                (host, Some(port), None)
-            } else {
-                split_host(host)
-            };
-            RedisConnectionAddr::Tcp {
+            tls
+        };
+        match HostAddr::parse(host, port, 6379) {
+            HostAddr::Unix(path) => RedisConnectionAddr::Unix { path: path.into() },
+            HostAddr::Tcp(host, port) => RedisConnectionAddr::Tcp {
                 host: host.into(),
-                port: port.unwrap_or(6379),
+                port,
                 tls,
-            }
+            },
+        }
+    }
+}
+
+/// A server configured by host and optional port, where the host can also be a unix socket path
+enum HostAddr<'a> {
+    Tcp(&'a str, u16),
+    Unix(&'a str),
+}
+
+impl<'a> HostAddr<'a> {
+    /// If no port is provided, the host can contain the port as `host:port`
+    fn parse(host: &'a str, port: Option<u16>, default_port: u16) -> Self {
+        if host.starts_with("/") {
+            return HostAddr::Unix(host);
+        }
+        let host = if host == "localhost" {
+            "127.0.0.1"
+        } else {
+            host
+        };
+        let (host, port, _) = if let Some(port) = port {
+            (host, Some(port), None)
+        } else {
+            split_host(host)
+        };
+        HostAddr::Tcp(host, port.unwrap_or(default_port))
+    }
+}
+
+/// The memcached servers and options, used by `\OC\Memcache\Memcached`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemcachedConfig {
+    pub servers: Vec<MemcachedServer>,
+    /// The `memcached_options`, keyed by the name of the `\Memcached::OPT_*` constant like `OPT_CONNECT_TIMEOUT`
+    ///
+    /// Options without a known constant are keyed by their number.
+    pub options: BTreeMap<String, Value>,
+}
+
+impl MemcachedConfig {
+    /// Get an option by constant name, with or without the `\Memcached::` prefix
+    pub fn option(&self, name: &str) -> Option<&Value> {
+        let name = name.trim_start_matches('\\');
+        self.options
+            .get(name.strip_prefix("Memcached::").unwrap_or(name))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemcachedServer {
+    pub addr: MemcachedAddr,
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, PartialOrd, PartialEq, Ord, Eq)]
+pub enum MemcachedAddr {
+    Tcp { host: String, port: u16 },
+    Unix { path: PathBuf },
+}
+
+impl MemcachedAddr {
+    fn parse(host: &str, port: Option<u16>) -> Self {
+        // sockets are configured with port 0
+        match HostAddr::parse(host, port.filter(|port| *port != 0), 11211) {
+            HostAddr::Unix(path) => MemcachedAddr::Unix { path: path.into() },
+            HostAddr::Tcp(host, port) => MemcachedAddr::Tcp {
+                host: host.into(),
+                port,
+            },
         }
     }
 }
@@ -74,6 +507,13 @@ pub struct RedisClusterConnectionInfo {
     pub username: Option<String>,
     pub password: Option<String>,
     pub tls_params: Option<RedisTlsParams>,
+    /// Timeout for connecting, `None` if connecting shouldn't time out
+    pub timeout: Option<Duration>,
+    /// Timeout for reading responses, `None` if reading shouldn't time out
+    pub read_timeout: Option<Duration>,
+    /// Whether persistent connections are used, set by the top-level `redis.persistent`
+    pub persistent: bool,
+    pub failover_mode: FailoverMode,
 }
 
 impl RedisClusterConnectionInfo {
@@ -84,8 +524,44 @@ impl RedisClusterConnectionInfo {
             username: self.username.clone(),
             password: self.password.clone(),
             tls_params: self.tls_params.clone(),
+            timeout: self.timeout,
+            read_timeout: self.read_timeout,
+            persistent: self.persistent,
         })
     }
+
+    /// Get connection urls for each of the cluster nodes, see [`RedisConnectionInfo::url`]
+    pub fn urls(&self) -> Vec<String> {
+        self.iter().map(|node| node.url()).collect()
+    }
+}
+
+/// How a redis cluster distributes reads to replicas, set with `\RedisCluster::FAILOVER_*`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailoverMode {
+    /// Only read from the primaries
+    #[default]
+    None,
+    /// Read from a replica if a primary can't be reached
+    Error,
+    /// Distribute reads between primaries and replicas
+    Distribute,
+    /// Distribute reads between replicas
+    DistributeSlaves,
+}
+
+impl TryFrom<i64> for FailoverMode {
+    type Error = i64;
+
+    fn try_from(mode: i64) -> Result<Self, i64> {
+        match mode {
+            0 => Ok(FailoverMode::None),
+            1 => Ok(FailoverMode::Error),
+            2 => Ok(FailoverMode::Distribute),
+            3 => Ok(FailoverMode::DistributeSlaves),
+            mode => Err(mode),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -95,6 +571,12 @@ pub struct RedisConnectionInfo {
     pub username: Option<String>,
     pub password: Option<String>,
     pub tls_params: Option<RedisTlsParams>,
+    /// Timeout for connecting, `None` if connecting shouldn't time out
+    pub timeout: Option<Duration>,
+    /// Timeout for reading responses, `None` if reading shouldn't time out
+    pub read_timeout: Option<Duration>,
+    /// Whether persistent connections are used, set by the top-level `redis.persistent`
+    pub persistent: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -106,6 +588,63 @@ pub struct RedisTlsParams {
     pub insecure: bool,
 }
 
+impl RedisConnectionInfo {
+    /// Get a connection url as understood by the `redis` crate
+    ///
+    /// Uses `redis://` or `rediss://` for tcp connections and `redis+unix://` for unix sockets,
+    /// connections that don't verify the tls certificate get the `#insecure` fragment.
+    pub fn url(&self) -> String {
+        match &self.addr {
+            RedisConnectionAddr::Tcp { host, port, tls } => {
+                let scheme = if *tls { "rediss" } else { "redis" };
+                let auth = match (&self.username, &self.password) {
+                    (None, None) => String::new(),
+                    (Some(username), None) => format!("{}@", urlencoding::encode(username)),
+                    (username, Some(password)) => format!(
+                        "{}:{}@",
+                        urlencoding::encode(username.as_deref().unwrap_or_default()),
+                        urlencoding::encode(password)
+                    ),
+                };
+                let host = if host.contains(':') {
+                    format!("[{host}]")
+                } else {
+                    host.clone()
+                };
+                let port = if *port == 6379 {
+                    String::new()
+                } else {
+                    format!(":{port}")
+                };
+                let db = if self.db == 0 {
+                    String::new()
+                } else {
+                    format!("/{}", self.db)
+                };
+                let insecure = *tls && self.tls_params.as_ref().is_some_and(|tls| tls.insecure);
+                let fragment = if insecure { "#insecure" } else { "" };
+                format!("{scheme}://{auth}{host}{port}{db}{fragment}")
+            }
+            RedisConnectionAddr::Unix { path } => {
+                let mut params = Serializer::new(String::new());
+                if self.db != 0 {
+                    params.append_pair("db", &self.db.to_string());
+                }
+                if let Some(username) = &self.username {
+                    params.append_pair("user", username);
+                }
+                if let Some(password) = &self.password {
+                    params.append_pair("pass", password);
+                }
+                let params = params.finish();
+                let params_start = if params.is_empty() { "" } else { "?" };
+                let path = urlencoding::encode(&path.to_string_lossy()).replace("%2F", "/");
+                format!("redis+unix://{path}{params_start}{params}")
+            }
+        }
+    }
+}
+
 impl RedisConfig {
     pub fn addr(&self) -> impl Iterator<Item = &RedisConnectionAddr> {
         match self {
@@ -154,19 +693,58 @@ pub enum Error {
     NotAConfig(#[from] NotAConfigError),
     #[error("Failed to read config file")]
     ReadFailed(std::io::Error, PathBuf),
-    #[error("invalid database configuration: {0}")]
-    InvalidDb(#[from] DbError),
+    #[error("invalid database configuration: {error}")]
+    InvalidDb {
+        #[source]
+        error: DbError,
+        #[source_code]
+        src: Option<NamedSource<String>>,
+        #[label("{error}")]
+        span: Option<SourceSpan>,
+    },
     #[error("Invalid redis configuration")]
     Redis,
     #[error("`overwrite.cli.url` not set`")]
-    NoUrl,
+    NoUrl {
+        #[source_code]
+        src: Option<NamedSource<String>>,
+        #[label("`overwrite.cli.url` is not set in this config")]
+        span: Option<SourceSpan>,
+    },
+    #[error("No nextcloud installation found at {}", .0.display())]
+    NoInstallation(PathBuf),
+    #[error("{} is not a valid nextcloud version file", .0.display())]
+    InvalidVersionFile(PathBuf),
+    #[error("Failed to write config file {}", .1.display())]
+    WriteFailed(std::io::Error, PathBuf),
+    #[error("Can't edit {} since {}", .0.display(), .1)]
+    NotEditable(PathBuf, &'static str),
+    #[cfg(feature = "watch")]
+    #[error("Failed to watch config directory {}", .1.display())]
+    Watch(std::io::Error, PathBuf),
+    #[cfg(feature = "redis")]
+    #[error("Failed to read tls certificate {}", .1.display())]
+    ReadCertificate(std::io::Error, PathBuf),
+    #[cfg(feature = "redis")]
+    #[error("Invalid redis connection: {0}")]
+    RedisClient(#[from] redis::RedisError),
+}
+
+impl From<DbError> for Error {
+    fn from(error: DbError) -> Self {
+        Error::InvalidDb {
+            error,
+            src: None,
+            span: None,
+        }
+    }
 }
 
 #[derive(Debug, Error, Diagnostic)]
 #[error("Error while parsing '{path}':\n{err}")]
 #[diagnostic(forward(err))]
 pub struct PhpParseError {
-    err: php_literal_parser::ParseError,
+    err: php::EvalError,
     path: PathBuf,
 }
 
@@ -1,106 +1,301 @@
+use crate::constants::CONFIG_CONSTANTS;
+use crate::discover::{find_installation, Installation};
+use crate::php::{self, Context, ItemSpan};
+use crate::source::{ConfigSource, FileSystemSource};
+use crate::version::{parse_version_file, VersionInfo};
 use crate::{
-    split_host, Config, Database, DbConnect, DbError, Error, NotAConfigError, PhpParseError,
-    RedisClusterConnectionInfo, RedisConnectionInfo, RedisTlsParams, Result, SslOptions,
+    split_host, Config, ConfigWarning, Database, DbConnect, DbError, DuplicateKey, Error, Instance,
+    NotAConfigError, PartialConfig, PhpParseError, RedisClusterConnectionInfo, RedisConnectionInfo,
+    RedisTlsParams, Result, SslOptions, ValueSource,
 };
-use crate::{RedisConfig, RedisConnectionAddr};
-use php_literal_parser::Value;
+use crate::{
+    FailoverMode, MemcacheConfig, MemcachedAddr, MemcachedConfig, MemcachedServer, RedisConfig,
+    RedisConnectionAddr,
+};
+use miette::{NamedSource, SourceSpan};
+use php_literal_parser::{Key, Value};
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fs::DirEntry;
+use std::io::Read;
 use std::iter::once;
 use std::net::IpAddr;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
-static CONFIG_CONSTANTS: &[(&str, &str)] = &[
-    (r"\RedisCluster::FAILOVER_NONE", "0"),
-    (r"\RedisCluster::FAILOVER_ERROR", "1"),
-    (r"\RedisCluster::DISTRIBUTE", "2"),
-    (r"\RedisCluster::FAILOVER_DISTRIBUTE_SLAVES", "3"),
-    (r"\PDO::MYSQL_ATTR_SSL_KEY", "1007"),
-    (r"\PDO::MYSQL_ATTR_SSL_CERT", "1008"),
-    (r"\PDO::MYSQL_ATTR_SSL_CA", "1009"),
-    (r"\PDO::MYSQL_ATTR_SSL_VERIFY_SERVER_CERT", "1014"),
-];
-
-fn glob_config_files(path: impl AsRef<Path>) -> impl Iterator<Item = PathBuf> {
-    let main: PathBuf = path.as_ref().into();
-    let files = if let Some(parent) = path.as_ref().parent() {
-        if let Ok(dir) = parent.read_dir() {
-            Some(dir.filter_map(Result::ok).filter_map(|file: DirEntry| {
-                let path = file.path();
-                match path.to_str() {
-                    Some(path_str) if path_str.ends_with(".config.php") => Some(path),
-                    _ => None,
-                }
-            }))
-        } else {
-            None
-        }
-    } else {
-        None
-    };
+/// List the config files in the order nextcloud merges them
+///
+/// Nextcloud loads `config.php` first, followed by all `*.config.php` files in the same directory
+/// in natural sort order, later files overwrite keys from earlier ones.
+pub(crate) fn glob_config_files(path: &Path, source: &dyn ConfigSource) -> Vec<PathBuf> {
+    let mut files = path
+        .parent()
+        .and_then(|parent| source.list_files(parent).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_extra_config_file)
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    once(path.into()).chain(files).collect()
+}
 
-    once(main).chain(files.into_iter().flatten())
+/// Whether a file name matches the `*.config.php` glob used for additional config files
+pub(crate) fn is_extra_config_file(name: &str) -> bool {
+    // php's `glob` doesn't match hidden files
+    name.ends_with(".config.php") && !name.starts_with('.')
 }
 
-fn parse_php(path: impl AsRef<Path>) -> Result<Value> {
-    let mut content = std::fs::read_to_string(&path)
-        .map_err(|err| Error::ReadFailed(err, path.as_ref().into()))?;
+/// Compare two strings like php's `natsort`, comparing runs of digits by their numeric value
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    fn chunks(str: &str) -> impl Iterator<Item = &str> {
+        str.as_bytes()
+            .chunk_by(|a, b| a.is_ascii_digit() == b.is_ascii_digit())
+            .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+    }
 
-    for (search, replace) in CONFIG_CONSTANTS {
-        if content.contains(search) {
-            content = content.replace(search, replace);
+    for (a, b) in chunks(a).zip(chunks(b)) {
+        let ordering = match (a.parse::<u128>(), b.parse::<u128>()) {
+            (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num).then_with(|| a.cmp(b)),
+            _ => a.cmp(b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
         }
     }
+    a.len().cmp(&b.len())
+}
 
-    let php = match content.find("$CONFIG") {
-        Some(pos) => content[pos + "$CONFIG".len()..]
-            .trim()
-            .trim_start_matches('='),
-        None => {
-            return Err(Error::NotAConfig(NotAConfigError::NoConfig(
-                path.as_ref().into(),
-            )));
-        }
-    };
-    php_literal_parser::from_str(php).map_err(|err| {
+struct ParsedFile {
+    path: PathBuf,
+    content: String,
+    config: Value,
+    /// Where `$CONFIG` is first assigned
+    config_span: Range<usize>,
+    sources: HashMap<String, ValueSource>,
+}
+
+fn parse_php(path: &Path, source: &dyn ConfigSource, ctx: Context) -> Result<ParsedFile> {
+    let content = source
+        .read_file(path)
+        .map_err(|err| Error::ReadFailed(err, path.into()))?;
+
+    let mut output = php::execute(&content, ctx).map_err(|err| {
         Error::Php(PhpParseError {
             err,
-            path: path.as_ref().into(),
+            path: path.into(),
+        })
+    })?;
+    let config = output
+        .variables
+        .remove("CONFIG")
+        .ok_or_else(|| Error::NotAConfig(NotAConfigError::NoConfig(path.into())))?;
+    let sources = output
+        .item_spans
+        .remove("CONFIG")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, ItemSpan { item: span, .. })| {
+            let line = content[..span.start].matches('\n').count() + 1;
+            let source = ValueSource::File {
+                path: path.into(),
+                span,
+                line,
+            };
+            (key.to_string(), source)
         })
+        .collect();
+    let config_span = output.variable_spans.remove("CONFIG").unwrap_or_default();
+
+    Ok(ParsedFile {
+        path: path.into(),
+        content,
+        config,
+        config_span,
+        sources,
     })
 }
 
-fn merge_configs(input: Vec<(PathBuf, Value)>) -> Result<Value> {
+struct MergedConfig {
+    config: Value,
+    duplicate_keys: Vec<DuplicateKey>,
+    sources: HashMap<String, ValueSource>,
+}
+
+fn merge_configs(input: Vec<ParsedFile>) -> Result<MergedConfig> {
     let mut merged = HashMap::with_capacity(16);
+    let mut defined_in: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    let mut sources = HashMap::with_capacity(16);
 
-    for (path, config) in input {
-        match config.into_hashmap() {
+    for file in input {
+        match file.config.into_hashmap() {
             Some(map) => {
-                for (key, value) in map {
-                    merged.insert(key, value);
+                for key in map.keys().filter_map(Key::as_str) {
+                    defined_in
+                        .entry(key.into())
+                        .or_default()
+                        .push(file.path.clone());
                 }
+                php::array_merge(&mut merged, map);
+                sources.extend(file.sources);
             }
             None => {
-                return Err(Error::NotAConfig(NotAConfigError::NotAnArray(path)));
+                return Err(Error::NotAConfig(NotAConfigError::NotAnArray(file.path)));
             }
         }
     }
 
-    Ok(Value::Array(merged))
+    let mut duplicate_keys = defined_in
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(key, defined_in)| DuplicateKey { key, defined_in })
+        .collect::<Vec<_>>();
+    duplicate_keys.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(MergedConfig {
+        config: Value::Array(merged),
+        duplicate_keys,
+        sources,
+    })
 }
 
-fn parse_files(files: impl IntoIterator<Item = PathBuf>) -> Result<Config> {
-    let parsed_files = files
+/// Apply `NC_<key>` environment variables on top of the merged config, like nextcloud does
+///
+/// Returns the keys that have been overridden
+fn apply_env_overrides(config: &mut Value, ctx: Context) -> Vec<String> {
+    let Value::Array(map) = config else {
+        return Vec::new();
+    };
+    let mut overridden = ctx
+        .env_with_prefix("NC_")
         .into_iter()
-        .map(|path| {
-            let parsed = parse_php(&path)?;
-            Result::<_, Error>::Ok((path, parsed))
+        .filter(|(name, _)| name.len() > "NC_".len())
+        .map(|(name, value)| {
+            let key = name["NC_".len()..].to_string();
+            let map_key = php::to_key(&Value::String(key.clone()));
+            let value = coerce_env_value(value, map.get(&map_key));
+            map.insert(map_key, value);
+            key
         })
-        .collect::<Result<Vec<_>, _>>()?;
-    let parsed = merge_configs(parsed_files)?;
+        .collect::<Vec<_>>();
+    overridden.sort();
+    overridden
+}
+
+/// Environment variables are always strings, convert them to the type of the value they override
+fn coerce_env_value(value: String, existing: Option<&Value>) -> Value {
+    match existing {
+        Some(Value::Bool(_)) => match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Value::Bool(true),
+            "" | "0" | "false" | "no" | "off" => Value::Bool(false),
+            _ => Value::String(value),
+        },
+        Some(Value::Int(_)) => match value.trim().parse() {
+            Ok(int) => Value::Int(int),
+            Err(_) => Value::String(value),
+        },
+        Some(Value::Float(_)) => match value.trim().parse() {
+            Ok(float) => Value::Float(float),
+            Err(_) => Value::String(value),
+        },
+        _ => Value::String(value),
+    }
+}
+
+fn parse_files(
+    files: impl IntoIterator<Item = PathBuf>,
+    source: &dyn ConfigSource,
+    options: &ConfigParser,
+    in_memory: bool,
+) -> Result<Config> {
+    let partial = parse_files_partial(files, source, options, false, in_memory)?;
+    Ok(Config {
+        database: partial.database?,
+        database_prefix: partial.database_prefix,
+        nextcloud_url: partial.nextcloud_url?,
+        redis: partial.redis,
+        memcache: partial.memcache,
+        memcached: partial.memcached,
+        env_overrides: partial.env_overrides,
+        duplicate_keys: partial.duplicate_keys,
+        sources: partial.sources,
+        raw: partial.raw,
+    })
+}
+
+/// Parse the config files, keeping errors for individual sections
+///
+/// In lenient mode, additional config files that fail to parse are skipped with a warning.
+/// Configs that aren't read from a file are parsed `in_memory`, without a known location for
+/// `__DIR__` or inferring the server root.
+fn parse_files_partial(
+    files: impl IntoIterator<Item = PathBuf>,
+    source: &dyn ConfigSource,
+    options: &ConfigParser,
+    lenient: bool,
+    in_memory: bool,
+) -> Result<PartialConfig> {
+    let files = files.into_iter().collect::<Vec<_>>();
+    let config_path = files.first().filter(|_| !in_memory);
+    let server_root = options.server_root_for(config_path.map(PathBuf::as_path));
+    let ctx = Context {
+        server_root: server_root.as_deref(),
+        ..options.context()
+    };
+    let mut warnings = Vec::new();
+    let mut parsed_files = Vec::new();
+    for (i, path) in files.into_iter().enumerate() {
+        let file = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+        let ctx = Context {
+            file: (!in_memory).then_some(file.as_path()),
+            ..ctx
+        };
+        let parsed = parse_php(&path, source, ctx).and_then(|parsed| match parsed.config {
+            Value::Array(_) => Ok(parsed),
+            _ => Err(Error::NotAConfig(NotAConfigError::NotAnArray(path.clone()))),
+        });
+        match parsed {
+            Ok(parsed) => parsed_files.push(parsed),
+            // like nextcloud, we ignore additional config files that don't set `$CONFIG` to an array
+            Err(Error::NotAConfig(
+                NotAConfigError::NoConfig(_) | NotAConfigError::NotAnArray(_),
+            )) if i > 0 => {}
+            Err(error) if i > 0 && lenient => {
+                warnings.push(ConfigWarning::SkippedFile { path, error });
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    let files = SourceFiles::new(&parsed_files);
+    let MergedConfig {
+        config: mut parsed,
+        duplicate_keys,
+        mut sources,
+    } = merge_configs(parsed_files)?;
+    let env_overrides = if options.env_overrides {
+        apply_env_overrides(&mut parsed, ctx)
+    } else {
+        Vec::new()
+    };
+    for key in &env_overrides {
+        sources.insert(
+            key.clone(),
+            ValueSource::Env {
+                name: format!("NC_{key}"),
+            },
+        );
+    }
 
-    let database = parse_db_options(&parsed)?;
+    let database = parse_db_options(&parsed).map_err(|error| {
+        let (src, span) = files.locate(&sources, &[db_error_key(&error), "dbtype"]);
+        Error::InvalidDb { error, src, span }
+    });
     let database_prefix = parsed["dbtableprefix"]
         .as_str()
         .unwrap_or("oc_")
@@ -108,26 +303,298 @@ fn parse_files(files: impl IntoIterator<Item = PathBuf>) -> Result<Config> {
     let nextcloud_url = parsed["overwrite.cli.url"]
         .clone()
         .into_string()
-        .ok_or(Error::NoUrl)?;
-    let redis = parse_redis_options(&parsed);
+        .ok_or_else(|| {
+            let (src, span) = files.locate(&sources, &["overwrite.cli.url"]);
+            Error::NoUrl { src, span }
+        });
+    let redis = parse_redis_options(&parsed, &mut warnings);
+    let memcache = parse_memcache_options(&parsed, &mut warnings);
+    let memcached = parse_memcached_options(&parsed, &mut warnings);
 
-    Ok(Config {
+    Ok(PartialConfig {
         database,
         database_prefix,
         nextcloud_url,
         redis,
+        memcache,
+        memcached,
+        env_overrides,
+        duplicate_keys,
+        sources,
+        raw: parsed,
+        warnings,
     })
 }
 
+/// Nextcloud's config files are stored in `<server root>/config`
+fn infer_server_root(config_path: &Path) -> Option<PathBuf> {
+    let config_path = std::path::absolute(config_path).ok()?;
+    Some(config_path.parent()?.parent()?.into())
+}
+
+/// The contents of the parsed config files, used to point errors at the relevant part of the config
+struct SourceFiles {
+    contents: HashMap<PathBuf, String>,
+    main: Option<(PathBuf, Range<usize>)>,
+}
+
+impl SourceFiles {
+    fn new(files: &[ParsedFile]) -> Self {
+        SourceFiles {
+            contents: files
+                .iter()
+                .map(|file| (file.path.clone(), file.content.clone()))
+                .collect(),
+            main: files
+                .first()
+                .map(|file| (file.path.clone(), file.config_span.clone())),
+        }
+    }
+
+    /// Find the first of the keys that is defined in a config file,
+    /// falling back to the `$CONFIG` variable of the main config file
+    fn locate(
+        &self,
+        sources: &HashMap<String, ValueSource>,
+        keys: &[&str],
+    ) -> (Option<NamedSource<String>>, Option<SourceSpan>) {
+        let (path, span) = keys
+            .iter()
+            .find_map(|key| match sources.get(*key) {
+                Some(ValueSource::File { path, span, .. }) => Some((path, span)),
+                _ => None,
+            })
+            .or_else(|| self.main.as_ref().map(|(path, span)| (path, span)))
+            .unzip();
+        let src = path.and_then(|path| {
+            let content = self.contents.get(path)?;
+            Some(NamedSource::new(path.to_string_lossy(), content.clone()).with_language("PHP"))
+        });
+        (src, span.map(|span| span.clone().into()))
+    }
+}
+
+/// The config key most relevant to a database error
+fn db_error_key(error: &DbError) -> &'static str {
+    match error {
+        DbError::Unsupported(_) => "dbtype",
+        DbError::NoUsername => "dbuser",
+        DbError::NoPassword => "dbpassword",
+        DbError::NoDataDirectory => "datadirectory",
+    }
+}
+
+/// Parser for nextcloud config files with non-default options
+#[derive(Debug, Clone, Default)]
+pub struct ConfigParser {
+    env: Option<HashMap<String, String>>,
+    env_overrides: bool,
+    source: Option<Arc<dyn ConfigSource>>,
+    server_root: Option<PathBuf>,
+    constants: HashMap<String, Value>,
+}
+
+impl ConfigParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the provided variables instead of the process environment when evaluating `getenv` calls
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Let `NC_<key>` environment variables override config values, like nextcloud does
+    pub fn with_env_overrides(mut self, enabled: bool) -> Self {
+        self.env_overrides = enabled;
+        self
+    }
+
+    /// Define a constant that can be used in the config files, in addition to the built-in ones
+    ///
+    /// Class constants are defined as `Class::NAME`, a leading `\` is optional
+    pub fn with_constant(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        self.constants
+            .insert(name.trim_start_matches('\\').into(), value.into());
+        self
+    }
+
+    /// Use the provided nextcloud installation directory for `OC::$SERVERROOT`
+    ///
+    /// By default, the server root is assumed to be the parent of the directory containing the config file
+    pub fn with_server_root(mut self, server_root: impl Into<PathBuf>) -> Self {
+        self.server_root = Some(server_root.into());
+        self
+    }
+
+    /// Load config files from the provided source instead of the local filesystem
+    pub fn with_source(mut self, source: impl ConfigSource + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn has_custom_source(&self) -> bool {
+        self.source.is_some()
+    }
+
+    pub(crate) fn source(&self) -> &dyn ConfigSource {
+        match &self.source {
+            Some(source) => source.as_ref(),
+            None => &FileSystemSource,
+        }
+    }
+
+    /// The configured server root, or the one inferred from the location of the main config file
+    pub(crate) fn server_root_for(&self, config_path: Option<&Path>) -> Option<PathBuf> {
+        self.server_root
+            .clone()
+            .or_else(|| infer_server_root(config_path?))
+    }
+
+    pub(crate) fn context(&self) -> Context<'_> {
+        Context {
+            env: self.env.as_ref(),
+            constants: CONFIG_CONSTANTS,
+            custom_constants: Some(&self.constants),
+            source: Some(self.source()),
+            ..Context::default()
+        }
+    }
+
+    /// Find the config of the nextcloud installation at `dir`
+    ///
+    /// `dir` can be the installation directory, the config or data directory, a docker volume
+    /// containing the installation or any directory of a snap or all-in-one installation.
+    /// The `NEXTCLOUD_CONFIG_DIR` environment variable is respected if set.
+    pub fn discover(&self, dir: impl AsRef<Path>) -> Result<Installation> {
+        let dir = dir.as_ref();
+        find_installation(dir, self.source(), self.context())
+            .ok_or_else(|| Error::NoInstallation(dir.into()))
+    }
+
+    /// Find and parse the config and `version.php` of the nextcloud installation at `dir`
+    ///
+    /// See [`discover`](Self::discover) for how the installation is found.
+    pub fn parse_instance(&self, dir: impl AsRef<Path>) -> Result<Instance> {
+        let dir = dir.as_ref();
+        let installation = self.discover(dir)?;
+        let mut options = self.clone();
+        options.server_root = options
+            .server_root
+            .or_else(|| installation.server_root.clone());
+        let server_root = options
+            .server_root
+            .as_deref()
+            .ok_or_else(|| Error::NoInstallation(dir.into()))?;
+
+        let version = options.parse_version(server_root.join("version.php"))?;
+        let config = options.parse_glob(&installation.config_file)?;
+        Ok(Instance {
+            config,
+            version,
+            installation,
+        })
+    }
+
+    /// Parse nextcloud's `version.php`
+    pub fn parse_version(&self, path: impl AsRef<Path>) -> Result<VersionInfo> {
+        parse_version_file(path.as_ref(), self.source(), self.context())
+    }
+
+    pub fn parse(&self, path: impl AsRef<Path>) -> Result<Config> {
+        parse_files(once(path.as_ref().into()), self.source(), self, false)
+    }
+
+    pub fn parse_glob(&self, path: impl AsRef<Path>) -> Result<Config> {
+        let source = self.source();
+        parse_files(
+            glob_config_files(path.as_ref(), source),
+            source,
+            self,
+            false,
+        )
+    }
+
+    /// Parse a single config file, returning the sections that could be parsed
+    ///
+    /// Only fails if the config file itself can't be read or parsed.
+    pub fn parse_lenient(&self, path: impl AsRef<Path>) -> Result<PartialConfig> {
+        parse_files_partial(once(path.as_ref().into()), self.source(), self, true, false)
+    }
+
+    /// Parse a config file and all `*.config.php` files next to it, returning the sections that could be parsed
+    ///
+    /// Only fails if the main config file can't be read or parsed, additional config files that
+    /// fail to parse are skipped with a warning.
+    pub fn parse_glob_lenient(&self, path: impl AsRef<Path>) -> Result<PartialConfig> {
+        let source = self.source();
+        parse_files_partial(
+            glob_config_files(path.as_ref(), source),
+            source,
+            self,
+            true,
+            false,
+        )
+    }
+
+    /// Parse the contents of a single config file
+    ///
+    /// Errors and value sources refer to the content as `config.php`.
+    /// Since the location of the config is unknown, `__DIR__` can't be used and
+    /// `OC::$SERVERROOT` can only be used if the server root is set with [`with_server_root`](Self::with_server_root).
+    pub fn parse_str(&self, content: &str) -> Result<Config> {
+        let path = PathBuf::from(STR_CONFIG_PATH);
+        let source = HashMap::from([(path.clone(), content.to_string())]);
+        parse_files(once(path), &source, self, true)
+    }
+
+    /// Read and parse a single config file
+    ///
+    /// Errors and value sources refer to the content as `config.php`
+    pub fn parse_reader(&self, mut reader: impl Read) -> Result<Config> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .map_err(|err| Error::ReadFailed(err, STR_CONFIG_PATH.into()))?;
+        self.parse_str(&content)
+    }
+}
+
+/// Path used for configs that aren't parsed from a file
+const STR_CONFIG_PATH: &str = "config.php";
+
 pub fn parse(path: impl AsRef<Path>) -> Result<Config> {
-    parse_files(once(path.as_ref().into()))
+    ConfigParser::new().parse(path)
 }
 
 pub fn parse_glob(path: impl AsRef<Path>) -> Result<Config> {
-    parse_files(glob_config_files(path))
+    ConfigParser::new().parse_glob(path)
+}
+
+pub fn discover(dir: impl AsRef<Path>) -> Result<Installation> {
+    ConfigParser::new().discover(dir)
+}
+
+pub fn parse_instance(dir: impl AsRef<Path>) -> Result<Instance> {
+    ConfigParser::new().parse_instance(dir)
+}
+
+pub fn parse_version(path: impl AsRef<Path>) -> Result<VersionInfo> {
+    ConfigParser::new().parse_version(path)
+}
+
+pub fn parse_str(content: &str) -> Result<Config> {
+    ConfigParser::new().parse_str(content)
+}
+
+pub fn parse_reader(reader: impl Read) -> Result<Config> {
+    ConfigParser::new().parse_reader(reader)
 }
 
-fn parse_db_options(parsed: &Value) -> Result<Database> {
+fn parse_db_options(parsed: &Value) -> Result<Database, DbError> {
     match parsed["dbtype"].as_str() {
         Some("mysql") => {
             let username = parsed["dbuser"].as_str().ok_or(DbError::NoUsername)?;
@@ -165,12 +632,12 @@ fn parse_db_options(parsed: &Value) -> Result<Database> {
                         unreachable!()
                     }
                 };
-            if let Some(port) = parsed["dbport"].clone().into_int() {
+            if let Some(port) = parse_port(&parsed["dbport"]) {
                 if let DbConnect::Tcp {
                     port: connect_port, ..
                 } = &mut connect
                 {
-                    *connect_port = port as u16;
+                    *connect_port = port;
                 }
             }
             let database = parsed["dbname"].as_str().unwrap_or("owncloud");
@@ -242,12 +709,12 @@ fn parse_db_options(parsed: &Value) -> Result<Database> {
                         unreachable!()
                     }
                 };
-            if let Some(port) = parsed["dbport"].clone().into_int() {
+            if let Some(port) = parse_port(&parsed["dbport"]) {
                 if let DbConnect::Tcp {
                     port: connect_port, ..
                 } = &mut connect
                 {
-                    *connect_port = port as u16;
+                    *connect_port = port;
                 }
             }
             let database = parsed["dbname"].as_str().unwrap_or("owncloud");
@@ -275,7 +742,15 @@ fn parse_db_options(parsed: &Value) -> Result<Database> {
                 database: format!("{}/{}.db", data_dir, db_name).into(),
             })
         }
-        Some(ty) => Err(Error::InvalidDb(DbError::Unsupported(ty.into()))),
+        Some(ty) => Err(DbError::Unsupported(ty.into())),
+    }
+}
+
+fn parse_port(value: &Value) -> Option<u16> {
+    match value {
+        Value::Int(port) => Some(*port as u16),
+        Value::String(port) => port.parse().ok(),
+        _ => None,
     }
 }
 
@@ -284,12 +759,113 @@ enum RedisAddress {
     Cluster(Vec<RedisConnectionAddr>),
 }
 
-fn parse_redis_options(parsed: &Value) -> RedisConfig {
+impl RedisAddress {
+    fn config_key(&self) -> &'static str {
+        match self {
+            RedisAddress::Single(_) => "redis",
+            RedisAddress::Cluster(_) => "redis.cluster",
+        }
+    }
+}
+
+fn parse_memcache_options(parsed: &Value, warnings: &mut Vec<ConfigWarning>) -> MemcacheConfig {
+    let mut backend = |key: &str| {
+        let class = parsed[key].as_str().filter(|class| !class.is_empty())?;
+        let backend = class.parse().ok();
+        if backend.is_none() {
+            warnings.push(ConfigWarning::IgnoredValue {
+                key: key.into(),
+                reason: "unknown memcache class",
+            });
+        }
+        backend
+    };
+    let local = backend("memcache.local");
+    let distributed = backend("memcache.distributed").or(local);
+    let locking = backend("memcache.locking");
+    MemcacheConfig {
+        local,
+        distributed,
+        locking,
+    }
+}
+
+fn parse_memcached_options(parsed: &Value, warnings: &mut Vec<ConfigWarning>) -> MemcachedConfig {
+    let non_empty = |value: &Value| value.iter().next().is_some();
+    // like nextcloud, fall back to the single `memcached_server` and then to the default server
+    let configured = if non_empty(&parsed["memcached_servers"]) {
+        let mut servers = parsed["memcached_servers"].iter().collect::<Vec<_>>();
+        servers.sort_by_key(|(key, _)| *key);
+        servers.into_iter().map(|(_, server)| server).collect()
+    } else if non_empty(&parsed["memcached_server"]) {
+        vec![&parsed["memcached_server"]]
+    } else {
+        Vec::new()
+    };
+    let servers = if configured.is_empty() {
+        vec![MemcachedServer {
+            addr: MemcachedAddr::parse("localhost", Some(11211)),
+            weight: 0,
+        }]
+    } else {
+        configured
+            .into_iter()
+            .filter_map(|server| {
+                let server = server[0].as_str().map(|host| MemcachedServer {
+                    addr: MemcachedAddr::parse(host, parse_port(&server[1])),
+                    weight: server[2]
+                        .as_int()
+                        .and_then(|weight| u32::try_from(weight).ok())
+                        .unwrap_or_default(),
+                });
+                if server.is_none() {
+                    warnings.push(ConfigWarning::IgnoredValue {
+                        key: "memcached_servers".into(),
+                        reason: "server is not a [host, port] array",
+                    });
+                }
+                server
+            })
+            .collect()
+    };
+
+    let options = parsed["memcached_options"]
+        .iter()
+        .map(|(key, value)| {
+            let name = match key {
+                Key::Int(option) => CONFIG_CONSTANTS
+                    .iter()
+                    .find(|(name, constant)| {
+                        constant == option && name.starts_with(r"\Memcached::OPT_")
+                    })
+                    .map_or_else(
+                        || option.to_string(),
+                        |(name, _)| name.trim_start_matches(r"\Memcached::").into(),
+                    ),
+                Key::String(name) => name.clone(),
+            };
+            (name, value.clone())
+        })
+        .collect();
+
+    MemcachedConfig { servers, options }
+}
+
+fn parse_redis_options(parsed: &Value, warnings: &mut Vec<ConfigWarning>) -> RedisConfig {
     let (redis_options, address) = if parsed["redis.cluster"].is_array() {
         let redis_options = &parsed["redis.cluster"];
         let seeds = redis_options["seeds"].values();
         let mut addresses = seeds
-            .filter_map(|seed| seed.as_str())
+            .filter_map(|seed| {
+                let seed = seed.as_str();
+                if seed.is_none() {
+                    warnings.push(ConfigWarning::IgnoredValue {
+                        key: "redis.cluster.seeds".into(),
+                        reason: "seed is not a string",
+                    });
+                }
+                seed
+            })
             .map(|seed| {
                 RedisConnectionAddr::parse(seed, None, redis_options["ssl_context"].is_array())
             })
@@ -299,11 +875,18 @@ fn parse_redis_options(parsed: &Value) -> RedisConfig {
     } else {
         let redis_options = &parsed["redis"];
         let host = redis_options["host"].as_str().unwrap_or("127.0.0.1");
+        let port = redis_options["port"]
+            .as_int()
+            .and_then(|port| u16::try_from(port).ok());
+        if port.is_none() && redis_options["port"].as_int().is_some() {
+            warnings.push(ConfigWarning::IgnoredValue {
+                key: "redis.port".into(),
+                reason: "port is out of range",
+            });
+        }
         let address = RedisAddress::Single(RedisConnectionAddr::parse(
             host,
-            redis_options["port"]
-                .as_int()
-                .and_then(|port| u16::try_from(port).ok()),
+            port,
             redis_options["ssl_context"].is_array(),
         ));
         (redis_options, address)
@@ -316,7 +899,7 @@ fn parse_redis_options(parsed: &Value) -> RedisConfig {
             local_pk: ssl_options["local_pk"].as_str().map(From::from),
             ca_file: ssl_options["cafile"].as_str().map(From::from),
             accept_invalid_hostname: ssl_options["verify_peer_name"] == false,
-            insecure: ssl_options["verify_peer "] == false,
+            insecure: ssl_options["verify_peer"] == false,
         })
     } else {
         None
@@ -332,6 +915,24 @@ fn parse_redis_options(parsed: &Value) -> RedisConfig {
         .filter(|user| !user.is_empty())
         .map(String::from);
 
+    let mut parse_timeout_option = |key: &str| {
+        let timeout = parse_timeout(&redis_options[key]);
+        if timeout.is_err() {
+            warnings.push(ConfigWarning::IgnoredValue {
+                key: format!("{}.{key}", address.config_key()),
+                reason: "timeout is not a number",
+            });
+        }
+        timeout.unwrap_or_default()
+    };
+    let timeout = parse_timeout_option("timeout");
+    let read_timeout = parse_timeout_option("read_timeout");
+    // nextcloud uses persistent connections unless disabled
+    let persistent = match &parsed["redis.persistent"] {
+        Value::Null => true,
+        persistent => php::is_truthy(persistent),
+    };
+
     match address {
         RedisAddress::Single(addr) => RedisConfig::Single(RedisConnectionInfo {
             addr,
@@ -339,28 +940,64 @@ fn parse_redis_options(parsed: &Value) -> RedisConfig {
             username,
             password,
             tls_params,
+            timeout,
+            read_timeout,
+            persistent,
         }),
-        RedisAddress::Cluster(addr) => RedisConfig::Cluster(RedisClusterConnectionInfo {
-            addr,
-            db,
-            username,
-            password,
-            tls_params,
-        }),
+        RedisAddress::Cluster(addr) => {
+            let failover_mode = match &redis_options["failover_mode"] {
+                Value::Null => FailoverMode::None,
+                mode => mode
+                    .as_int()
+                    .and_then(|mode| FailoverMode::try_from(mode).ok())
+                    .unwrap_or_else(|| {
+                        warnings.push(ConfigWarning::IgnoredValue {
+                            key: "redis.cluster.failover_mode".into(),
+                            reason: "unknown failover mode",
+                        });
+                        FailoverMode::None
+                    }),
+            };
+            RedisConfig::Cluster(RedisClusterConnectionInfo {
+                addr,
+                db,
+                username,
+                password,
+                tls_params,
+                timeout,
+                read_timeout,
+                persistent,
+                failover_mode,
+            })
+        }
     }
 }
 
+/// Parse a timeout in seconds, where `0` disables the timeout
+fn parse_timeout(value: &Value) -> Result<Option<Duration>, ()> {
+    let seconds = match value {
+        Value::Null => return Ok(None),
+        Value::Int(seconds) => *seconds as f64,
+        Value::Float(seconds) => *seconds,
+        Value::String(seconds) => seconds.trim().parse().map_err(|_| ())?,
+        _ => return Err(()),
+    };
+    Ok(Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|timeout| !timeout.is_zero()))
+}
+
 #[test]
 fn test_redis_empty_password_none() {
     let config =
         php_literal_parser::from_str(r#"["redis" => ["host" => "redis", "password" => "pass"]]"#)
             .unwrap();
-    let redis = parse_redis_options(&config);
+    let redis = parse_redis_options(&config, &mut Vec::new());
     assert_eq!(redis.passwd(), Some("pass"));
 
     let config =
         php_literal_parser::from_str(r#"["redis" => ["host" => "redis", "password" => ""]]"#)
             .unwrap();
-    let redis = parse_redis_options(&config);
+    let redis = parse_redis_options(&config, &mut Vec::new());
     assert_eq!(redis.passwd(), None);
 }
@@ -0,0 +1,157 @@
+use crate::nc::is_extra_config_file;
+use crate::{Config, ConfigParser, ConfigSource, Error, Result};
+use std::collections::HashMap;
+use std::io;
+use std::panic::resume_unwind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+impl ConfigParser {
+    /// Parse a single config file without blocking the async runtime
+    ///
+    /// Configs from a custom [`ConfigSource`](crate::ConfigSource) are loaded on tokio's blocking thread pool.
+    pub async fn parse_async(&self, path: impl AsRef<Path>) -> Result<Config> {
+        let path = path.as_ref();
+        if self.has_custom_source() {
+            return self.parse_blocking(path, ConfigParser::parse).await;
+        }
+
+        let files = read_files(vec![path.into()]).await?;
+        self.parse_prefetched(files, |parser| parser.parse(path))
+            .await
+    }
+
+    /// Parse a config file and all `*.config.php` files next to it without blocking the async runtime
+    ///
+    /// Configs from a custom [`ConfigSource`](crate::ConfigSource) are loaded on tokio's blocking thread pool.
+    pub async fn parse_glob_async(&self, path: impl AsRef<Path>) -> Result<Config> {
+        let path = path.as_ref();
+        if self.has_custom_source() {
+            return self.parse_blocking(path, ConfigParser::parse_glob).await;
+        }
+
+        let mut paths = vec![path.to_path_buf()];
+        if let Some(parent) = path.parent() {
+            paths.extend(list_extra_config_files(parent).await);
+        }
+        let files = read_files(paths).await?;
+        self.parse_prefetched(files, |parser| parser.parse_glob(path))
+            .await
+    }
+
+    /// Parse from files read ahead of time
+    ///
+    /// Files read by `file_get_contents` aren't known before evaluating the config,
+    /// the config is parsed again after reading any files that were missing.
+    async fn parse_prefetched(
+        &self,
+        files: HashMap<PathBuf, String>,
+        parse: impl Fn(&ConfigParser) -> Result<Config>,
+    ) -> Result<Config> {
+        let mut files = files
+            .into_iter()
+            .map(|(path, content)| (path, Some(content)))
+            .collect();
+        loop {
+            let source = PrefetchedFiles {
+                files,
+                missing: Default::default(),
+            };
+            let missing = source.missing.clone();
+            let parser = self.clone().with_source(source.clone());
+            let result = parse(&parser);
+            let missing = std::mem::take(&mut *missing.lock().unwrap());
+            if missing.is_empty() {
+                return result;
+            }
+            files = source.files;
+            for path in missing {
+                let content = tokio::fs::read_to_string(&path).await.ok();
+                files.insert(path, content);
+            }
+        }
+    }
+
+    async fn parse_blocking(
+        &self,
+        path: &Path,
+        parse: fn(&ConfigParser, PathBuf) -> Result<Config>,
+    ) -> Result<Config> {
+        let parser = self.clone();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || parse(&parser, path))
+            .await
+            .unwrap_or_else(|err| resume_unwind(err.into_panic()))
+    }
+}
+
+pub async fn parse_async(path: impl AsRef<Path>) -> Result<Config> {
+    ConfigParser::new().parse_async(path).await
+}
+
+pub async fn parse_glob_async(path: impl AsRef<Path>) -> Result<Config> {
+    ConfigParser::new().parse_glob_async(path).await
+}
+
+/// List the `*.config.php` files in a directory, the order is determined by the parser
+async fn list_extra_config_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    // like the blocking parser, an unreadable directory is treated as not having extra config files
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(is_extra_config_file)
+        {
+            files.push(path);
+        }
+    }
+    files
+}
+
+async fn read_files(paths: Vec<PathBuf>) -> Result<HashMap<PathBuf, String>> {
+    let mut files = HashMap::with_capacity(paths.len());
+    for path in paths {
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => {
+                files.insert(path, content);
+            }
+            Err(err) => return Err(Error::ReadFailed(err, path)),
+        }
+    }
+    Ok(files)
+}
+
+/// Files read ahead of time, `None` for files that couldn't be read
+#[derive(Debug, Clone)]
+struct PrefetchedFiles {
+    files: HashMap<PathBuf, Option<String>>,
+    /// Files that were requested but haven't been read yet
+    missing: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl ConfigSource for PrefetchedFiles {
+    fn list_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .files
+            .iter()
+            .filter(|(path, content)| content.is_some() && path.parent() == Some(dir))
+            .map(|(path, _)| path.clone())
+            .collect())
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.files.get(path) {
+            Some(Some(content)) => Ok(content.clone()),
+            Some(None) => Err(io::ErrorKind::NotFound.into()),
+            None => {
+                self.missing.lock().unwrap().push(path.into());
+                Err(io::ErrorKind::NotFound.into())
+            }
+        }
+    }
+}
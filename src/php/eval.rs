@@ -0,0 +1,570 @@
+use super::parser::{BinaryOp, Cast, Expr, ExprKind, Statement};
+use super::{
+    array_merge, is_truthy, to_float, to_int, to_key, to_number, to_string, ArraySpan, Context,
+    ItemSpan, ItemSpans, Result, SyntaxError, SyntaxErrorKind,
+};
+use php_literal_parser::{Key, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+pub struct Evaluator<'a> {
+    source: &'a str,
+    ctx: Context<'a>,
+    pub variables: HashMap<String, Value>,
+    pub item_spans: HashMap<String, ItemSpans>,
+    pub variable_spans: HashMap<String, Range<usize>>,
+    pub array_spans: HashMap<String, ArraySpan>,
+    /// Whether the statements being executed are the body of an `if` without braces
+    braceless: bool,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(source: &'a str, ctx: Context<'a>) -> Self {
+        Evaluator {
+            source,
+            ctx,
+            variables: HashMap::new(),
+            item_spans: HashMap::new(),
+            variable_spans: HashMap::new(),
+            array_spans: HashMap::new(),
+            braceless: false,
+        }
+    }
+
+    fn error(&self, span: Range<usize>, kind: SyntaxErrorKind) -> super::EvalError {
+        SyntaxError::new(self.source, span, kind).into()
+    }
+
+    pub fn execute(&mut self, statements: &[Statement]) -> Result<()> {
+        for statement in statements {
+            match statement {
+                Statement::Assign { target, value } => self.assign(target, value)?,
+                Statement::If {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut taken = None;
+                    for (condition, body) in branches {
+                        if is_truthy(&self.eval(condition)?) {
+                            taken = Some(body);
+                            break;
+                        }
+                    }
+                    let body = taken.unwrap_or(otherwise);
+                    let outer = std::mem::replace(&mut self.braceless, body.braceless);
+                    self.execute(&body.statements)?;
+                    self.braceless = outer;
+                }
+                Statement::Expression(expr) => {
+                    self.eval(expr)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn assign(&mut self, target: &Expr, value: &Expr) -> Result<()> {
+        let mut keys = Vec::new();
+        let mut base = target;
+        while let ExprKind::Index(inner, key) = &base.kind {
+            keys.push(match key {
+                Some(key) => Some(to_key(&self.eval(key)?)),
+                None => None,
+            });
+            base = inner;
+        }
+        let ExprKind::Variable(name) = &base.kind else {
+            return Err(self.error(
+                target.span.clone(),
+                SyntaxErrorKind::UnexpectedToken("variable or array element"),
+            ));
+        };
+
+        self.variable_spans
+            .entry(name.clone())
+            .or_insert_with(|| base.span.clone());
+        let value_span = value.span.clone();
+        let value = match keys.last() {
+            None => {
+                if let ExprKind::Array(items) = &value.kind {
+                    let items = items
+                        .iter()
+                        .map(|(key, value)| {
+                            key.as_ref().map_or(value.span.start, |key| key.span.start)
+                                ..value.span.end
+                        })
+                        .collect();
+                    let span = ArraySpan {
+                        array: value_span.clone(),
+                        items,
+                    };
+                    self.array_spans.insert(name.clone(), span);
+                } else {
+                    self.array_spans.remove(name);
+                }
+                let (value, spans) = self.eval_spanned(value)?;
+                self.item_spans.insert(name.clone(), spans);
+                value
+            }
+            Some(top_level_key) => {
+                let evaluated = self.eval(value)?;
+                if let Some(key) = top_level_key {
+                    self.item_spans.entry(name.clone()).or_default().insert(
+                        key.clone(),
+                        ItemSpan {
+                            item: target.span.start..value_span.end,
+                            value: (keys.len() == 1).then_some(value_span),
+                            removable: !self.braceless,
+                        },
+                    );
+                }
+                evaluated
+            }
+        };
+
+        let mut slot = self.variables.entry(name.clone()).or_insert(Value::Null);
+        for key in keys.into_iter().rev() {
+            if !slot.is_array() {
+                *slot = Value::Array(HashMap::new());
+            }
+            let Value::Array(map) = slot else {
+                unreachable!();
+            };
+            let key = match key.or_else(|| next_index(map).map(Key::Int)) {
+                Some(key) => key,
+                None => {
+                    return Err(SyntaxError::new(
+                        self.source,
+                        target.span.clone(),
+                        SyntaxErrorKind::NextIndexOccupied,
+                    )
+                    .into());
+                }
+            };
+            slot = map.entry(key).or_insert(Value::Null);
+        }
+        *slot = value;
+        Ok(())
+    }
+
+    /// Evaluate an expression while keeping track of where the items of the resulting array are defined
+    fn eval_spanned(&self, expr: &Expr) -> Result<(Value, ItemSpans)> {
+        Ok(match &expr.kind {
+            ExprKind::Array(items) => self.array(items)?,
+            ExprKind::Variable(name) => (
+                self.eval(expr)?,
+                self.item_spans.get(name).cloned().unwrap_or_default(),
+            ),
+            ExprKind::Call(name, args)
+                if name
+                    .trim_start_matches('\\')
+                    .eq_ignore_ascii_case("array_merge") =>
+            {
+                let mut merged = HashMap::new();
+                let mut spans = ItemSpans::new();
+                for arg in args {
+                    let (value, arg_spans) = self.eval_spanned(arg)?;
+                    // integer keys are renumbered while merging
+                    spans.extend(arg_spans.into_iter().filter(|(key, _)| key.is_string()));
+                    array_merge(&mut merged, value.into_hashmap().unwrap_or_default());
+                }
+                (Value::Array(merged), spans)
+            }
+            ExprKind::Coalesce(value, otherwise) => match self.eval_spanned(value)? {
+                (Value::Null, _) => self.eval_spanned(otherwise)?,
+                value => value,
+            },
+            ExprKind::Ternary(condition, then, otherwise) => {
+                let (condition, spans) = self.eval_spanned(condition)?;
+                match (is_truthy(&condition), then) {
+                    (true, Some(then)) => self.eval_spanned(then)?,
+                    (true, None) => (condition, spans),
+                    (false, _) => self.eval_spanned(otherwise)?,
+                }
+            }
+            _ => (self.eval(expr)?, ItemSpans::new()),
+        })
+    }
+
+    fn array(&self, items: &[(Option<Expr>, Expr)]) -> Result<(Value, ItemSpans)> {
+        let mut map = HashMap::with_capacity(items.len());
+        let mut spans = ItemSpans::with_capacity(items.len());
+        for (key, value) in items {
+            let (key, start) = match key {
+                Some(key) => (to_key(&self.eval(key)?), key.span.start),
+                None => match next_index(&map) {
+                    Some(index) => (Key::Int(index), value.span.start),
+                    None => {
+                        return Err(
+                            self.error(value.span.clone(), SyntaxErrorKind::NextIndexOccupied)
+                        );
+                    }
+                },
+            };
+            spans.insert(
+                key.clone(),
+                ItemSpan {
+                    item: start..value.span.end,
+                    value: Some(value.span.clone()),
+                    removable: true,
+                },
+            );
+            map.insert(key, self.eval(value)?);
+        }
+        Ok((Value::Array(map), spans))
+    }
+
+    pub fn eval(&self, expr: &Expr) -> Result<Value> {
+        Ok(match &expr.kind {
+            ExprKind::Literal(value) => value.clone(),
+            ExprKind::Array(items) => self.array(items)?.0,
+            ExprKind::Variable(name) => self.variables.get(name).cloned().unwrap_or(Value::Null),
+            ExprKind::Index(base, Some(key)) => {
+                let key = to_key(&self.eval(key)?);
+                match (self.eval(base)?, key) {
+                    (Value::Array(mut map), key) => map.remove(&key).unwrap_or(Value::Null),
+                    (Value::String(str), Key::Int(index)) => {
+                        let index = if index < 0 {
+                            str.len() as i64 + index
+                        } else {
+                            index
+                        };
+                        usize::try_from(index)
+                            .ok()
+                            .and_then(|index| str.get(index..index + 1))
+                            .map(Value::from)
+                            .unwrap_or(Value::Null)
+                    }
+                    _ => Value::Null,
+                }
+            }
+            ExprKind::Index(_, None) => {
+                return Err(self.error(
+                    expr.span.clone(),
+                    SyntaxErrorKind::UnexpectedToken("array key"),
+                ));
+            }
+            ExprKind::Constant(name) => match name.to_ascii_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => match self
+                    .magic_constant(name)
+                    .or_else(|| self.ctx.constant(name))
+                {
+                    Some(value) => value,
+                    None => {
+                        return Err(self.error(
+                            expr.span.clone(),
+                            SyntaxErrorKind::UnknownConstant(name.clone()),
+                        ));
+                    }
+                },
+            },
+            ExprKind::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>>>()?;
+                self.call(name, args, &expr.span)?
+            }
+            ExprKind::Not(inner) => Value::Bool(!is_truthy(&self.eval(inner)?)),
+            ExprKind::Negate(inner) => match self.eval(inner)? {
+                Value::Float(float) => Value::Float(-float),
+                // negating `PHP_INT_MIN` overflows into a float
+                value => {
+                    let int = to_int(&value);
+                    int.checked_neg()
+                        .map_or_else(|| Value::Float(-(int as f64)), Value::Int)
+                }
+            },
+            ExprKind::Cast(cast, inner) => {
+                let value = self.eval(inner)?;
+                match cast {
+                    Cast::String => Value::String(to_string(&value)),
+                    Cast::Int => Value::Int(to_int(&value)),
+                    Cast::Bool => Value::Bool(is_truthy(&value)),
+                    Cast::Float => Value::Float(to_float(&value)),
+                }
+            }
+            ExprKind::Binary(op, left, right) => {
+                let left = self.eval(left)?;
+                match op {
+                    BinaryOp::And => Value::Bool(is_truthy(&left) && is_truthy(&self.eval(right)?)),
+                    BinaryOp::Or => Value::Bool(is_truthy(&left) || is_truthy(&self.eval(right)?)),
+                    BinaryOp::Equal => Value::Bool(loose_equal(&left, &self.eval(right)?)),
+                    BinaryOp::NotEqual => Value::Bool(!loose_equal(&left, &self.eval(right)?)),
+                    BinaryOp::Identical => Value::Bool(left == self.eval(right)?),
+                    BinaryOp::NotIdentical => Value::Bool(left != self.eval(right)?),
+                    BinaryOp::Less => Value::Bool(
+                        loose_compare(&left, &self.eval(right)?).is_some_and(Ordering::is_lt),
+                    ),
+                    BinaryOp::LessEqual => Value::Bool(
+                        loose_compare(&left, &self.eval(right)?).is_some_and(Ordering::is_le),
+                    ),
+                    BinaryOp::Greater => Value::Bool(
+                        loose_compare(&left, &self.eval(right)?).is_some_and(Ordering::is_gt),
+                    ),
+                    BinaryOp::GreaterEqual => Value::Bool(
+                        loose_compare(&left, &self.eval(right)?).is_some_and(Ordering::is_ge),
+                    ),
+                    BinaryOp::Concat => {
+                        Value::String(to_string(&left) + &to_string(&self.eval(right)?))
+                    }
+                    BinaryOp::Add
+                    | BinaryOp::Subtract
+                    | BinaryOp::Multiply
+                    | BinaryOp::Divide
+                    | BinaryOp::Modulo => arithmetic(*op, left, self.eval(right)?)
+                        .map_err(|kind| self.error(expr.span.clone(), kind))?,
+                }
+            }
+            ExprKind::StaticProperty(class, name) => {
+                match (
+                    class.trim_start_matches('\\'),
+                    name.as_str(),
+                    self.ctx.server_root,
+                ) {
+                    ("OC", "SERVERROOT", Some(root)) => {
+                        Value::String(root.to_string_lossy().into())
+                    }
+                    _ => {
+                        return Err(self.error(
+                            expr.span.clone(),
+                            SyntaxErrorKind::UnknownConstant(format!("{class}::${name}")),
+                        ));
+                    }
+                }
+            }
+            ExprKind::Coalesce(..) | ExprKind::Ternary(..) => self.eval_spanned(expr)?.0,
+        })
+    }
+
+    /// Constants built into php: `Foo::class`, `DIRECTORY_SEPARATOR`, `PHP_EOL`, `NAN`, `INF` and
+    /// `__DIR__` and `__FILE__` if the path of the evaluated file is known
+    fn magic_constant(&self, name: &str) -> Option<Value> {
+        if let Some((class, constant)) = name.rsplit_once("::") {
+            return constant
+                .eq_ignore_ascii_case("class")
+                .then(|| Value::String(class.trim_start_matches('\\').into()));
+        }
+        match name.trim_start_matches('\\') {
+            "DIRECTORY_SEPARATOR" => return Some("/".into()),
+            "PHP_EOL" => return Some("\n".into()),
+            "NAN" => return Some(Value::Float(f64::NAN)),
+            "INF" => return Some(Value::Float(f64::INFINITY)),
+            _ => {}
+        }
+        let file = self.ctx.file?;
+        let path = match name.to_ascii_uppercase().as_str() {
+            "__DIR__" => file.parent()?,
+            "__FILE__" => file,
+            _ => return None,
+        };
+        Some(Value::String(path.to_string_lossy().into()))
+    }
+
+    fn call(&self, name: &str, args: Vec<Value>, span: &Range<usize>) -> Result<Value> {
+        let arg = |n: usize| args.get(n).unwrap_or(&Value::Null);
+        Ok(
+            match name.trim_start_matches('\\').to_ascii_lowercase().as_str() {
+                "getenv" => self
+                    .ctx
+                    .getenv(&to_string(arg(0)))
+                    .map(Value::String)
+                    .unwrap_or(Value::Bool(false)),
+                "isset" => Value::Bool(args.iter().all(|arg| !arg.is_null())),
+                "empty" => Value::Bool(!is_truthy(arg(0))),
+                "trim" => Value::String(
+                    to_string(arg(0))
+                        .trim_matches([' ', '\t', '\n', '\r', '\0', '\x0B'])
+                        .into(),
+                ),
+                "file_get_contents" => self
+                    .ctx
+                    .read_file(Path::new(&to_string(arg(0))))
+                    .map(Value::String)
+                    .unwrap_or(Value::Bool(false)),
+                "explode" => {
+                    let separator = to_string(arg(0));
+                    let string = to_string(arg(1));
+                    list(string.split(separator.as_str()).map(Value::from))
+                }
+                "array_merge" => {
+                    let mut merged = HashMap::new();
+                    for arg in args {
+                        array_merge(&mut merged, arg.into_hashmap().unwrap_or_default());
+                    }
+                    Value::Array(merged)
+                }
+                "array_filter" => Value::Array(
+                    arg(0)
+                        .clone()
+                        .into_hashmap()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|(_, value)| is_truthy(value))
+                        .collect(),
+                ),
+                "array_map" => {
+                    let callback = to_string(arg(0));
+                    let array = arg(1).clone().into_hashmap().unwrap_or_default();
+                    let mut mapped = HashMap::with_capacity(array.len());
+                    for (key, value) in array {
+                        mapped.insert(key, self.call(&callback, vec![value], span)?);
+                    }
+                    Value::Array(mapped)
+                }
+                _ => {
+                    return Err(self.error(
+                        span.clone(),
+                        SyntaxErrorKind::UnsupportedFunction(name.into()),
+                    ));
+                }
+            },
+        )
+    }
+}
+
+/// The key for an item appended to the array, `None` if the largest key is `PHP_INT_MAX`
+pub(super) fn next_index(map: &HashMap<Key, Value>) -> Option<i64> {
+    match map.keys().filter_map(Key::as_int).max() {
+        Some(max) => max.checked_add(1),
+        None => Some(0),
+    }
+}
+
+fn list(items: impl Iterator<Item = Value>) -> Value {
+    Value::Array((0..).map(Key::Int).zip(items).collect())
+}
+
+/// Get the numeric value of a string if it is numeric
+fn numeric(str: &str) -> Option<f64> {
+    let str = str.trim();
+    if str.contains(|c: char| c.is_ascii_digit()) {
+        str.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Compare two values using php's `==` rules
+fn loose_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Bool(_), _) | (_, Value::Bool(_)) => is_truthy(a) == is_truthy(b),
+        (Value::Null, Value::Null) => true,
+        (Value::Null, Value::String(str)) | (Value::String(str), Value::Null) => str.is_empty(),
+        (Value::Null, other) | (other, Value::Null) => !is_truthy(other),
+        (Value::String(a), Value::String(b)) => match (numeric(a), numeric(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+        (Value::String(str), number @ (Value::Int(_) | Value::Float(_)))
+        | (number @ (Value::Int(_) | Value::Float(_)), Value::String(str)) => match numeric(str) {
+            Some(value) => value == to_float(number),
+            None => *str == to_string(number),
+        },
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| loose_equal(a, b)))
+        }
+        (Value::Array(_), _) | (_, Value::Array(_)) => false,
+        (a, b) => to_float(a) == to_float(b),
+    }
+}
+
+/// Compare two values using php's `<` and `>` rules, `None` if they can't be ordered
+fn loose_compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Bool(_), _) | (_, Value::Bool(_)) => Some(is_truthy(a).cmp(&is_truthy(b))),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Null, Value::String(str)) => Some("".cmp(str.as_str())),
+        (Value::String(str), Value::Null) => Some(str.as_str().cmp("")),
+        (Value::Null, _) | (_, Value::Null) => Some(is_truthy(a).cmp(&is_truthy(b))),
+        (Value::String(a), Value::String(b)) => match (numeric(a), numeric(b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some(a.cmp(b)),
+        },
+        (Value::String(str), number @ (Value::Int(_) | Value::Float(_))) => match numeric(str) {
+            Some(value) => value.partial_cmp(&to_float(number)),
+            None => Some(str.cmp(&to_string(number))),
+        },
+        (Value::Int(_) | Value::Float(_), Value::String(_)) => {
+            loose_compare(b, a).map(Ordering::reverse)
+        }
+        // arrays with fewer items are smaller, otherwise they are compared item by item
+        (Value::Array(a), Value::Array(b)) if a.len() != b.len() => Some(a.len().cmp(&b.len())),
+        (Value::Array(a), Value::Array(b)) => {
+            for (key, a) in a {
+                match loose_compare(a, b.get(key)?)? {
+                    Ordering::Equal => {}
+                    ordering => return Some(ordering),
+                }
+            }
+            Some(Ordering::Equal)
+        }
+        (Value::Array(_), _) => Some(Ordering::Greater),
+        (_, Value::Array(_)) => Some(Ordering::Less),
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (a, b) => to_float(a).partial_cmp(&to_float(b)),
+    }
+}
+
+/// Apply an arithmetic operator using php's rules, integer overflow results in a float
+fn arithmetic(op: BinaryOp, left: Value, right: Value) -> Result<Value, SyntaxErrorKind> {
+    match (op, left, right) {
+        // `+` on arrays is a union, keeping the left item for duplicate keys
+        (BinaryOp::Add, Value::Array(mut left), Value::Array(right)) => {
+            for (key, value) in right {
+                left.entry(key).or_insert(value);
+            }
+            Ok(Value::Array(left))
+        }
+        (_, Value::Array(_), _) | (_, _, Value::Array(_)) => {
+            Err(SyntaxErrorKind::UnsupportedOperands)
+        }
+        (BinaryOp::Modulo, left, right) => match to_int(&right) {
+            0 => Err(SyntaxErrorKind::DivisionByZero("Modulo")),
+            // `PHP_INT_MIN % -1` overflows but the result is always 0
+            divisor => Ok(Value::Int(to_int(&left).checked_rem(divisor).unwrap_or(0))),
+        },
+        (op, left, right) => match (to_number(&left), to_number(&right)) {
+            (_, right) if to_float(&right) == 0.0 && matches!(op, BinaryOp::Divide) => {
+                Err(SyntaxErrorKind::DivisionByZero("Division"))
+            }
+            (Value::Int(left), Value::Int(right)) => {
+                let int = match op {
+                    BinaryOp::Add => left.checked_add(right),
+                    BinaryOp::Subtract => left.checked_sub(right),
+                    BinaryOp::Multiply => left.checked_mul(right),
+                    // division only results in an int if there is no remainder
+                    _ => left
+                        .checked_rem(right)
+                        .filter(|rem| *rem == 0)
+                        .and_then(|_| left.checked_div(right)),
+                };
+                Ok(int.map_or_else(
+                    || Value::Float(float_arithmetic(op, left as f64, right as f64)),
+                    Value::Int,
+                ))
+            }
+            (left, right) => Ok(Value::Float(float_arithmetic(
+                op,
+                to_float(&left),
+                to_float(&right),
+            ))),
+        },
+    }
+}
+
+fn float_arithmetic(op: BinaryOp, left: f64, right: f64) -> f64 {
+    match op {
+        BinaryOp::Add => left + right,
+        BinaryOp::Subtract => left - right,
+        BinaryOp::Multiply => left * right,
+        _ => left / right,
+    }
+}
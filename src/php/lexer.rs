@@ -0,0 +1,293 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Variable,
+    Ident,
+    String,
+    /// Heredoc or nowdoc string, see [`heredoc_body`]
+    Heredoc,
+    Number,
+    BracketOpen,
+    BracketClose,
+    SquareOpen,
+    SquareClose,
+    CurlyOpen,
+    CurlyClose,
+    Comma,
+    SemiColon,
+    Arrow,
+    Assign,
+    Question,
+    Colon,
+    DoubleColon,
+    Dot,
+    Coalesce,
+    Not,
+    Minus,
+    Plus,
+    Star,
+    Slash,
+    Percent,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    Identical,
+    NotIdentical,
+    And,
+    Or,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c >= 0x80
+}
+
+fn is_ident_char(c: u8) -> bool {
+    is_ident_start(c) || c.is_ascii_digit()
+}
+
+fn ident_end(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && is_ident_char(bytes[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut pos = start + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            c if c == quote => return pos + 1,
+            _ => pos += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn number_end(bytes: &[u8], mut pos: usize) -> usize {
+    let hex = bytes.get(pos) == Some(&b'0') && matches!(bytes.get(pos + 1), Some(b'x' | b'X'));
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'.' => pos += 1,
+            b'+' | b'-' if !hex && matches!(bytes[pos - 1], b'e' | b'E') => pos += 1,
+            _ => break,
+        }
+    }
+    pos
+}
+
+fn line_end(bytes: &[u8], pos: usize) -> usize {
+    bytes[pos..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(bytes.len(), |end| pos + end)
+}
+
+/// Find the end of a heredoc or nowdoc starting at `<<<`, returns `None` if it's not a valid heredoc
+fn heredoc_end(source: &str, start: usize) -> Option<usize> {
+    let (label, body_start) = heredoc_label(source, start)?;
+    let mut line_start = body_start;
+    loop {
+        let line = &source[line_start..];
+        let indented = line.trim_start_matches([' ', '\t']);
+        if let Some(rest) = indented.strip_prefix(label) {
+            if !rest.bytes().next().is_some_and(is_ident_char) {
+                return Some(source.len() - rest.len());
+            }
+        }
+        line_start += line.find('\n')? + 1;
+    }
+}
+
+/// Get the label of a heredoc starting at `<<<` and the start of its body
+fn heredoc_label(source: &str, start: usize) -> Option<(&str, usize)> {
+    let header = source[start + 3..].trim_start_matches([' ', '\t']);
+    let header_start = source.len() - header.len();
+    let quote = header
+        .as_bytes()
+        .first()
+        .filter(|c| matches!(c, b'\'' | b'"'));
+    let label_start = header_start + usize::from(quote.is_some());
+    let label_end = ident_end(source.as_bytes(), label_start);
+    if label_end == label_start || !is_ident_start(source.as_bytes()[label_start]) {
+        return None;
+    }
+    let mut header_end = label_end;
+    if let Some(quote) = quote {
+        if source.as_bytes().get(header_end) != Some(quote) {
+            return None;
+        }
+        header_end += 1;
+    }
+    let body_start = header_end + source[header_end..].strip_prefix("\r\n").map_or(1, |_| 2);
+    match source.as_bytes().get(header_end) {
+        Some(b'\n' | b'\r') => Some((&source[label_start..label_end], body_start)),
+        _ => None,
+    }
+}
+
+/// Get the contents of a heredoc or nowdoc token and whether it is a nowdoc
+///
+/// The indentation of the closing label is removed from all lines, like php does
+pub fn heredoc_body(token: &str) -> (String, bool) {
+    let nowdoc = token[3..].trim_start_matches([' ', '\t']).starts_with('\'');
+    let Some((label, body_start)) = heredoc_label(token, 0) else {
+        return (String::new(), nowdoc);
+    };
+    let closing_line_start = token.rfind('\n').map_or(0, |pos| pos + 1);
+    let indent = token.len() - label.len() - closing_line_start;
+    let body = token
+        .get(body_start..closing_line_start.saturating_sub(1))
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+    let body = body
+        .split('\n')
+        .map(|line| {
+            let strip = line
+                .bytes()
+                .take(indent)
+                .take_while(|c| matches!(c, b' ' | b'\t'))
+                .count();
+            &line[strip..]
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    (body, nowdoc)
+}
+
+/// Find the end of the next `<?php` open tag, everything before it is output by php as-is
+fn open_tag_end(source: &str, pos: usize) -> usize {
+    source[pos..]
+        .find("<?php")
+        .map_or(source.len(), |start| pos + start + "<?php".len())
+}
+
+pub fn lex(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = open_tag_end(source, 0);
+
+    while pos < bytes.len() {
+        let start = pos;
+        let next = bytes.get(pos + 1).copied().unwrap_or_default();
+        let kind = match bytes[pos] {
+            c if c.is_ascii_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            b'#' => {
+                pos = line_end(bytes, pos);
+                continue;
+            }
+            b'/' if next == b'/' => {
+                pos = line_end(bytes, pos);
+                continue;
+            }
+            b'/' if next == b'*' => {
+                pos = match source[pos + 2..].find("*/") {
+                    Some(end) => pos + 2 + end + 2,
+                    None => bytes.len(),
+                };
+                continue;
+            }
+            // a close tag acts as statement terminator
+            b'?' if next == b'>' => {
+                tokens.push(Token {
+                    kind: TokenKind::SemiColon,
+                    span: start..start + 2,
+                });
+                pos = open_tag_end(source, pos + 2);
+                continue;
+            }
+            b'\'' | b'"' => {
+                pos = string_end(bytes, pos);
+                TokenKind::String
+            }
+            b'<' if source[pos..].starts_with("<<<") && heredoc_end(source, pos).is_some() => {
+                pos = heredoc_end(source, pos).unwrap_or(bytes.len());
+                TokenKind::Heredoc
+            }
+            b'$' if is_ident_start(next) => {
+                pos = ident_end(bytes, pos + 1);
+                TokenKind::Variable
+            }
+            b'0'..=b'9' => {
+                pos = number_end(bytes, pos);
+                TokenKind::Number
+            }
+            b'.' if next.is_ascii_digit() => {
+                pos = number_end(bytes, pos);
+                TokenKind::Number
+            }
+            c if is_ident_start(c) || (c == b'\\' && is_ident_start(next)) => {
+                pos = ident_end(bytes, pos + 1);
+                while bytes.get(pos) == Some(&b'\\')
+                    && bytes.get(pos + 1).copied().is_some_and(is_ident_start)
+                {
+                    pos = ident_end(bytes, pos + 1);
+                }
+                TokenKind::Ident
+            }
+            _ => {
+                let third = bytes.get(pos + 2).copied().unwrap_or_default();
+                let (kind, len) = match (bytes[pos], next, third) {
+                    (b'=', b'=', b'=') => (TokenKind::Identical, 3),
+                    (b'!', b'=', b'=') => (TokenKind::NotIdentical, 3),
+                    (b'=', b'=', _) => (TokenKind::Equal, 2),
+                    (b'!', b'=', _) | (b'<', b'>', _) => (TokenKind::NotEqual, 2),
+                    (b'<', b'=', _) => (TokenKind::LessEqual, 2),
+                    (b'>', b'=', _) => (TokenKind::GreaterEqual, 2),
+                    (b'=', b'>', _) => (TokenKind::Arrow, 2),
+                    (b'?', b'?', _) => (TokenKind::Coalesce, 2),
+                    (b':', b':', _) => (TokenKind::DoubleColon, 2),
+                    (b'&', b'&', _) => (TokenKind::And, 2),
+                    (b'|', b'|', _) => (TokenKind::Or, 2),
+                    (b'(', _, _) => (TokenKind::BracketOpen, 1),
+                    (b')', _, _) => (TokenKind::BracketClose, 1),
+                    (b'[', _, _) => (TokenKind::SquareOpen, 1),
+                    (b']', _, _) => (TokenKind::SquareClose, 1),
+                    (b'{', _, _) => (TokenKind::CurlyOpen, 1),
+                    (b'}', _, _) => (TokenKind::CurlyClose, 1),
+                    (b',', _, _) => (TokenKind::Comma, 1),
+                    (b';', _, _) => (TokenKind::SemiColon, 1),
+                    (b'=', _, _) => (TokenKind::Assign, 1),
+                    (b'?', _, _) => (TokenKind::Question, 1),
+                    (b':', _, _) => (TokenKind::Colon, 1),
+                    (b'!', _, _) => (TokenKind::Not, 1),
+                    (b'-', _, _) => (TokenKind::Minus, 1),
+                    (b'+', _, _) => (TokenKind::Plus, 1),
+                    (b'*', _, _) => (TokenKind::Star, 1),
+                    (b'/', _, _) => (TokenKind::Slash, 1),
+                    (b'%', _, _) => (TokenKind::Percent, 1),
+                    (b'<', _, _) => (TokenKind::Less, 1),
+                    (b'>', _, _) => (TokenKind::Greater, 1),
+                    (b'.', _, _) => (TokenKind::Dot, 1),
+                    _ => (
+                        TokenKind::Unknown,
+                        source[pos..].chars().next().map_or(1, char::len_utf8),
+                    ),
+                };
+                pos += len;
+                kind
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: start..pos,
+        });
+    }
+
+    tokens
+}
@@ -0,0 +1,626 @@
+mod eval;
+mod lexer;
+mod parser;
+
+use crate::source::{ConfigSource, FileSystemSource};
+use eval::Evaluator;
+use miette::{Diagnostic, SourceOffset, SourceSpan};
+use parser::Parser;
+use php_literal_parser::{Key, Value};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use thiserror::Error;
+
+/// Everything outside the php source that expressions can depend on
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Context<'a> {
+    /// Environment to use for `getenv`, the process environment is used if not set
+    pub env: Option<&'a HashMap<String, String>>,
+    /// Known constants and their values
+    pub constants: &'a [(&'a str, i64)],
+    /// Additional constants, stored without leading `\`
+    pub custom_constants: Option<&'a HashMap<String, Value>>,
+    /// Absolute path of the file being evaluated, used for `__DIR__` and `__FILE__`
+    pub file: Option<&'a Path>,
+    /// Nextcloud installation directory, used for `OC::$SERVERROOT`
+    pub server_root: Option<&'a Path>,
+    /// Where `file_get_contents` reads from, the local filesystem is used if not set
+    pub source: Option<&'a dyn ConfigSource>,
+}
+
+impl Context<'_> {
+    fn constant(&self, name: &str) -> Option<Value> {
+        let name = name.trim_start_matches('\\');
+        if let Some(value) = self
+            .custom_constants
+            .and_then(|constants| constants.get(name))
+        {
+            return Some(value.clone());
+        }
+        self.constants
+            .iter()
+            .find(|(constant, _)| constant.trim_start_matches('\\') == name)
+            .map(|(_, value)| Value::Int(*value))
+    }
+
+    pub fn getenv(&self, name: &str) -> Option<String> {
+        match self.env {
+            Some(env) => env.get(name).cloned(),
+            None => std::env::var(name).ok(),
+        }
+    }
+
+    pub fn read_file(&self, path: &Path) -> Option<String> {
+        match self.source {
+            Some(source) => source.read_file(path).ok(),
+            None => FileSystemSource.read_file(path).ok(),
+        }
+    }
+
+    /// All environment variables with a name starting with `prefix`
+    pub fn env_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        match self.env {
+            Some(env) => env
+                .iter()
+                .filter(|(name, _)| name.starts_with(prefix))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            None => std::env::vars_os()
+                .filter_map(|(name, value)| {
+                    Some((name.into_string().ok()?, value.into_string().ok()?))
+                })
+                .filter(|(name, _)| name.starts_with(prefix))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub(crate) enum EvalError {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Literal(#[from] php_literal_parser::ParseError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Syntax(#[from] SyntaxError),
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("{kind}")]
+#[diagnostic(code(nextcloud_config_parser::syntax))]
+pub(crate) struct SyntaxError {
+    #[source_code]
+    src: String,
+    #[label("{}", self.kind.label())]
+    err_span: SourceSpan,
+    kind: SyntaxErrorKind,
+}
+
+#[derive(Debug, Error)]
+enum SyntaxErrorKind {
+    #[error("Unexpected token, expected {0}")]
+    UnexpectedToken(&'static str),
+    #[error("Unexpected end of input, expected {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("Unsupported function `{0}`")]
+    UnsupportedFunction(String),
+    #[error("Unknown constant `{0}`")]
+    UnknownConstant(String),
+    #[error("Cannot add element to the array as the next element is already occupied")]
+    NextIndexOccupied,
+    #[error("{0} by zero")]
+    DivisionByZero(&'static str),
+    #[error("Unsupported operand types for arithmetic")]
+    UnsupportedOperands,
+}
+
+impl SyntaxErrorKind {
+    fn label(&self) -> &'static str {
+        match self {
+            SyntaxErrorKind::UnexpectedToken(expected) => expected,
+            SyntaxErrorKind::UnexpectedEnd(expected) => expected,
+            SyntaxErrorKind::UnsupportedFunction(_) => "unsupported function",
+            SyntaxErrorKind::UnknownConstant(_) => "unknown constant",
+            SyntaxErrorKind::NextIndexOccupied => "next element is already occupied",
+            SyntaxErrorKind::DivisionByZero(_) => "division by zero",
+            SyntaxErrorKind::UnsupportedOperands => "unsupported operand types",
+        }
+    }
+}
+
+impl SyntaxError {
+    fn new(source: &str, span: Range<usize>, kind: SyntaxErrorKind) -> Self {
+        SyntaxError {
+            src: source.into(),
+            err_span: SourceSpan::new(SourceOffset::from(span.start), span.len()),
+            kind,
+        }
+    }
+}
+
+type Result<T, E = EvalError> = std::result::Result<T, E>;
+
+/// Location of an array item in the source
+#[derive(Debug, Clone)]
+pub(crate) struct ItemSpan {
+    /// The `key => value` pair or `$array[key] = value` assignment
+    pub item: Range<usize>,
+    /// The value of the item, not set if the assignment only sets a nested item like `$array[key][nested] = value`
+    pub value: Option<Range<usize>>,
+    /// Whether the item can be removed without changing the meaning of the surrounding code,
+    /// which isn't the case for an assignment that is the body of an `if` without braces
+    pub removable: bool,
+}
+
+pub(crate) type ItemSpans = HashMap<Key, ItemSpan>;
+
+/// Location of an array literal in the source
+#[derive(Debug, Clone)]
+pub(crate) struct ArraySpan {
+    /// The whole literal, including the brackets
+    pub array: Range<usize>,
+    /// The items of the literal in source order, later assignments to the array are not included
+    pub items: Vec<Range<usize>>,
+}
+
+/// The state after running a php file
+pub(crate) struct Output {
+    pub variables: HashMap<String, Value>,
+    /// Where the items of array variables are defined
+    pub item_spans: HashMap<String, ItemSpans>,
+    /// Where variables are first assigned
+    pub variable_spans: HashMap<String, Range<usize>>,
+    /// The array literals that are assigned to variables, if the last assignment of the variable was one
+    pub array_spans: HashMap<String, ArraySpan>,
+}
+
+/// Run a php file and get the variables it defines
+pub(crate) fn execute(source: &str, ctx: Context) -> Result<Output> {
+    let statements = Parser::new(source).file()?;
+    let mut evaluator = Evaluator::new(source, ctx);
+    evaluator.execute(&statements)?;
+    Ok(Output {
+        variables: evaluator.variables,
+        item_spans: evaluator.item_spans,
+        variable_spans: evaluator.variable_spans,
+        array_spans: evaluator.array_spans,
+    })
+}
+
+/// Merge the items of `from` into `into` like php's `array_merge`
+///
+/// String keys overwrite existing items while integer keyed items are appended
+pub(crate) fn array_merge(into: &mut HashMap<Key, Value>, from: HashMap<Key, Value>) {
+    let mut appended = Vec::new();
+    for (key, value) in from {
+        match key {
+            Key::Int(index) => appended.push((index, value)),
+            key => {
+                into.insert(key, value);
+            }
+        }
+    }
+    appended.sort_by_key(|(index, _)| *index);
+    for (_, value) in appended {
+        // like php, items that can't be appended are dropped
+        let Some(index) = eval::next_index(into) else {
+            break;
+        };
+        into.insert(Key::Int(index), value);
+    }
+}
+
+/// Convert a value to bool using php's rules
+pub(crate) fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(bool) => *bool,
+        Value::Int(int) => *int != 0,
+        Value::Float(float) => *float != 0.0,
+        Value::String(str) => !(str.is_empty() || str == "0"),
+        Value::Array(map) => !map.is_empty(),
+        Value::Null => false,
+    }
+}
+
+/// Convert a value to string using php's rules
+pub(crate) fn to_string(value: &Value) -> String {
+    match value {
+        Value::Bool(true) => "1".into(),
+        Value::Bool(false) | Value::Null => String::new(),
+        Value::Int(int) => int.to_string(),
+        Value::Float(float) => format_float(*float, Some(14)),
+        Value::String(str) => str.clone(),
+        Value::Array(_) => "Array".into(),
+    }
+}
+
+/// Format a float like php does with the given number of significant digits
+///
+/// Without a precision the shortest representation that round-trips is used,
+/// php uses that for `var_export` while converting to string uses 14 digits.
+/// Large and small numbers use scientific notation like `1.0E+25`.
+pub(crate) fn format_float(float: f64, precision: Option<usize>) -> String {
+    if float.is_nan() {
+        return "NAN".into();
+    } else if float.is_infinite() {
+        return if float > 0.0 { "INF" } else { "-INF" }.into();
+    }
+
+    let formatted = match precision {
+        Some(precision) => format!("{:.*e}", precision.max(1) - 1, float.abs()),
+        None => format!("{:e}", float.abs()),
+    };
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or_default();
+    let digits = mantissa.replace('.', "");
+    let digits = match digits.trim_end_matches('0') {
+        "" => "0",
+        digits => digits,
+    };
+    // number of digits before the decimal point
+    let point = exponent + 1;
+
+    let mut out = String::new();
+    if float.is_sign_negative() {
+        out.push('-');
+    }
+    if point < -3 || point > precision.unwrap_or(17) as i32 {
+        out.push_str(&digits[..1]);
+        out.push('.');
+        out.push_str(if digits.len() > 1 { &digits[1..] } else { "0" });
+        out.push_str(if exponent < 0 { "E-" } else { "E+" });
+        out.push_str(&exponent.unsigned_abs().to_string());
+    } else if point <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat(point.unsigned_abs() as usize));
+        out.push_str(digits);
+    } else if digits.len() > point as usize {
+        let (int, fraction) = digits.split_at(point as usize);
+        out.push_str(int);
+        out.push('.');
+        out.push_str(fraction);
+    } else {
+        out.push_str(digits);
+        out.push_str(&"0".repeat(point as usize - digits.len()));
+    }
+    out
+}
+
+/// Convert a value to int using php's rules
+pub(crate) fn to_int(value: &Value) -> i64 {
+    match value {
+        Value::Bool(bool) => *bool as i64,
+        Value::Int(int) => *int,
+        Value::Float(float) => *float as i64,
+        Value::String(str) => {
+            let (digits, rest) = split_int_prefix(str);
+            match rest.chars().next() {
+                Some('.' | 'e' | 'E') => to_float(value) as i64,
+                // out of range integers saturate
+                _ => digits.parse().unwrap_or(if !has_digits(digits) {
+                    0
+                } else if digits.starts_with('-') {
+                    i64::MIN
+                } else {
+                    i64::MAX
+                }),
+            }
+        }
+        Value::Array(map) => !map.is_empty() as i64,
+        Value::Null => 0,
+    }
+}
+
+/// Convert a value to an int or float for arithmetic using php's rules
+///
+/// Unlike [`to_int`], out of range integer strings are converted to float
+pub(crate) fn to_number(value: &Value) -> Value {
+    match value {
+        Value::Float(_) => value.clone(),
+        Value::String(str) => {
+            let (digits, rest) = split_int_prefix(str);
+            match (rest.chars().next(), digits.parse()) {
+                (Some('.' | 'e' | 'E'), _) => Value::Float(to_float(value)),
+                (_, Ok(int)) => Value::Int(int),
+                (_, Err(_)) if has_digits(digits) => Value::Float(to_float(value)),
+                (_, Err(_)) => Value::Int(0),
+            }
+        }
+        value => Value::Int(to_int(value)),
+    }
+}
+
+/// Split a string into the leading integer, including its sign, and the remainder
+fn split_int_prefix(str: &str) -> (&str, &str) {
+    let str = str.trim_start();
+    let sign_len = usize::from(str.starts_with(['-', '+']));
+    let digits_end = str[sign_len..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(str.len(), |end| end + sign_len);
+    str.split_at(digits_end)
+}
+
+fn has_digits(int: &str) -> bool {
+    !int.trim_start_matches(['-', '+']).is_empty()
+}
+
+/// Convert a value to float using php's rules
+pub(crate) fn to_float(value: &Value) -> f64 {
+    match value {
+        Value::Float(float) => *float,
+        Value::String(str) => {
+            let str = str.trim_start();
+            let end = str
+                .char_indices()
+                .take_while(|(i, c)| {
+                    c.is_ascii_digit()
+                        || *c == '.'
+                        || (*i == 0 && matches!(c, '-' | '+'))
+                        || matches!(c, 'e' | 'E')
+                })
+                .map(|(i, c)| i + c.len_utf8())
+                .last()
+                .unwrap_or(0);
+            (0..=end)
+                .rev()
+                .find_map(|end| str[..end].parse::<f64>().ok())
+                .unwrap_or(0.0)
+        }
+        value => to_int(value) as f64,
+    }
+}
+
+/// Convert a value to an array key using php's rules
+pub(crate) fn to_key(value: &Value) -> Key {
+    match value {
+        Value::String(str) => match str.parse::<i64>() {
+            Ok(int) if int.to_string() == *str => Key::Int(int),
+            _ => Key::String(str.clone()),
+        },
+        Value::Null => Key::String(String::new()),
+        value => Key::Int(to_int(value)),
+    }
+}
+
+#[cfg(test)]
+fn evaluate(source: &str, ctx: Context) -> Value {
+    execute(&format!("<?php $result = {source};"), ctx)
+        .unwrap()
+        .variables["result"]
+        .clone()
+}
+
+#[test]
+fn test_evaluate_getenv() {
+    let env = HashMap::from([("FOO".to_string(), "foo".to_string())]);
+    let ctx = Context {
+        env: Some(&env),
+        ..Context::default()
+    };
+    assert_eq!(evaluate("getenv('FOO')", ctx), "foo");
+    assert_eq!(evaluate("getenv('BAR')", ctx), false);
+    assert_eq!(evaluate("getenv('BAR') ?: 'bar'", ctx), "bar");
+    assert_eq!(evaluate("(string) getenv('BAR')", ctx), "");
+    assert_eq!(evaluate("(int) getenv('BAR') ?: 6379", ctx), 6379);
+    assert_eq!(
+        evaluate("['a' => getenv('FOO'), 1, 5 => 2, 3]", ctx),
+        Value::Array(HashMap::from([
+            ("a".into(), "foo".into()),
+            (0.into(), 1.into()),
+            (5.into(), 2.into()),
+            (6.into(), 3.into()),
+        ]))
+    );
+}
+
+#[test]
+fn test_execute_statements() {
+    let env = HashMap::from([("REDIS_HOST".to_string(), "/run/redis.sock".to_string())]);
+    let ctx = Context {
+        env: Some(&env),
+        ..Context::default()
+    };
+    let variables = execute(
+        r#"<?php
+        $CONFIG = ['a' => 1, 5 => 'x'];
+        $CONFIG['b']['c'] = 2;
+        $CONFIG['list'][] = 'first';
+        if (getenv('REDIS_HOST')[0] != '/') {
+            $CONFIG['port'] = 6379;
+        } elseif (getenv('MISSING') !== false) {
+            $CONFIG['port'] = 1;
+        } else {
+            $CONFIG['socket'] = true;
+        }
+        if (isset($CONFIG['missing']) || empty($CONFIG['a'])) $CONFIG['a'] = 0;
+        $CONFIG = array_merge($CONFIG, array(6 => 'y', 'a' => 3));
+        ?>"#,
+        ctx,
+    )
+    .unwrap()
+    .variables;
+    let config = &variables["CONFIG"];
+    assert_eq!(config["a"], 3);
+    assert_eq!(config["b"]["c"], 2);
+    assert_eq!(config["list"][0], "first");
+    assert_eq!(config["socket"], true);
+    assert!(config["port"].is_null());
+    assert_eq!(config[0], "x");
+    assert_eq!(config[1], "y");
+}
+
+#[test]
+fn test_evaluate_conversions() {
+    assert_eq!(to_int(&"12abc".into()), 12);
+    assert_eq!(to_int(&"-3".into()), -3);
+    assert_eq!(to_int(&"1.9".into()), 1);
+    assert_eq!(to_int(&"abc".into()), 0);
+    assert_eq!(to_int(&"-".into()), 0);
+    assert_eq!(to_int(&"99999999999999999999".into()), i64::MAX);
+    assert_eq!(to_int(&"-99999999999999999999 apples".into()), i64::MIN);
+    assert!(!is_truthy(&"0".into()));
+    assert!(is_truthy(&"0.0".into()));
+    assert_eq!(to_string(&Value::Bool(true)), "1");
+    assert_eq!(to_string(&Value::Float(1.0)), "1");
+    assert_eq!(to_string(&Value::Float(-1.5)), "-1.5");
+    assert_eq!(to_string(&Value::Float(0.1 + 0.2)), "0.3");
+    assert_eq!(to_string(&Value::Float(1.0 / 3.0)), "0.33333333333333");
+    assert_eq!(to_string(&Value::Float(1e13)), "10000000000000");
+    assert_eq!(to_string(&Value::Float(1e20)), "1.0E+20");
+    assert_eq!(
+        to_string(&Value::Float(9.223372036854776E18)),
+        "9.2233720368548E+18"
+    );
+    assert_eq!(to_string(&Value::Float(0.0001)), "0.0001");
+    assert_eq!(to_string(&Value::Float(0.00001)), "1.0E-5");
+    assert_eq!(to_string(&Value::Float(-0.0)), "-0");
+    assert_eq!(to_string(&Value::Float(f64::NAN)), "NAN");
+    assert_eq!(format_float(0.1 + 0.2, None), "0.30000000000000004");
+    assert_eq!(format_float(1e20, None), "1.0E+20");
+}
+
+#[test]
+fn test_evaluate_paths() {
+    let ctx = Context {
+        file: Some(Path::new("/var/www/nextcloud/config/config.php")),
+        server_root: Some(Path::new("/var/www/nextcloud")),
+        ..Context::default()
+    };
+    assert_eq!(
+        evaluate("__DIR__ . '/../data'", ctx),
+        "/var/www/nextcloud/config/../data"
+    );
+    assert_eq!(
+        evaluate("__FILE__", ctx),
+        "/var/www/nextcloud/config/config.php"
+    );
+    assert_eq!(
+        evaluate(r"\OC::$SERVERROOT . '/apps'", ctx),
+        "/var/www/nextcloud/apps"
+    );
+    assert_eq!(evaluate("'a' . 1 . true . null", ctx), "a11");
+    assert_eq!(evaluate("'a' . 'b' == 'ab'", ctx), true);
+    assert!(execute("<?php $a = OC::$SERVERROOT;", Context::default()).is_err());
+    assert!(execute("<?php $a = __DIR__;", Context::default()).is_err());
+}
+
+#[test]
+fn test_evaluate_constants() {
+    let custom = HashMap::from([("Foo::BAR".to_string(), Value::from("bar"))]);
+    let ctx = Context {
+        constants: &[(r"\Redis::SERIALIZER_IGBINARY", 2)],
+        custom_constants: Some(&custom),
+        ..Context::default()
+    };
+    assert_eq!(evaluate(r"\Redis::SERIALIZER_IGBINARY", ctx), 2);
+    assert_eq!(evaluate(r"Redis::SERIALIZER_IGBINARY", ctx), 2);
+    assert_eq!(evaluate(r"\Foo::BAR", ctx), "bar");
+    assert_eq!(
+        evaluate(r"\OC\Files\ObjectStore\S3::class", ctx),
+        r"OC\Files\ObjectStore\S3"
+    );
+    assert_eq!(evaluate("DIRECTORY_SEPARATOR", ctx), "/");
+    assert!(execute(r"<?php $a = \Redis::OPT_PREFIX;", ctx).is_err());
+}
+
+#[test]
+fn test_evaluate_negate() {
+    let ctx = Context {
+        constants: &[("PHP_INT_MIN", i64::MIN)],
+        ..Context::default()
+    };
+    assert_eq!(evaluate("-'5'", ctx), -5);
+    assert_eq!(evaluate("-1.5", ctx), Value::Float(-1.5));
+    assert_eq!(
+        evaluate("-PHP_INT_MIN", ctx),
+        Value::Float(9.223372036854776E18)
+    );
+    assert_eq!(
+        evaluate("-(int)'-9223372036854775808'", ctx),
+        Value::Float(9.223372036854776E18)
+    );
+}
+
+#[test]
+fn test_evaluate_next_index_overflow() {
+    let ctx = Context {
+        constants: &[("PHP_INT_MAX", i64::MAX)],
+        ..Context::default()
+    };
+    assert_eq!(
+        evaluate("[PHP_INT_MAX - 1 => 'x', 'y']", ctx)[i64::MAX],
+        "y"
+    );
+    assert!(execute("<?php $a = [PHP_INT_MAX => 'x', 'y'];", ctx).is_err());
+    assert!(execute("<?php $a = [PHP_INT_MAX => 'x']; $a[] = 'y';", ctx).is_err());
+}
+
+#[test]
+fn test_evaluate_arithmetic() {
+    let ctx = Context {
+        constants: &[("PHP_INT_MAX", i64::MAX), ("PHP_INT_MIN", i64::MIN)],
+        ..Context::default()
+    };
+    assert_eq!(evaluate("60 * 60 * 24", ctx), 86400);
+    assert_eq!(evaluate("1 + 2 * 3 - 4", ctx), 3);
+    assert_eq!(evaluate("(1 + 2) * 3", ctx), 9);
+    assert_eq!(evaluate("10 - 2 - 3", ctx), 5);
+    assert_eq!(evaluate("-2 * 3", ctx), -6);
+    assert_eq!(evaluate("'5' + '1.5'", ctx), Value::Float(6.5));
+    assert_eq!(evaluate("6 / 2", ctx), 3);
+    assert_eq!(evaluate("7 / 2", ctx), Value::Float(3.5));
+    assert_eq!(evaluate("7 % 3", ctx), 1);
+    assert_eq!(evaluate("-7 % 3", ctx), -1);
+    assert_eq!(evaluate("7.9 % 3", ctx), 1);
+    assert_eq!(evaluate("PHP_INT_MIN % -1", ctx), 0);
+    // `.` has a lower precedence than `+` and `-`
+    assert_eq!(evaluate("'a' . 1 + 2", ctx), "a3");
+    assert_eq!(evaluate("1 + 2 . 'a'", ctx), "3a");
+    assert_eq!(
+        evaluate("PHP_INT_MAX + 1", ctx),
+        Value::Float(9.223372036854776E18)
+    );
+    assert_eq!(
+        evaluate("PHP_INT_MIN - 1", ctx),
+        Value::Float(-9.223372036854776E18)
+    );
+    assert_eq!(
+        evaluate("PHP_INT_MAX * 2", ctx),
+        Value::Float(1.8446744073709552E19)
+    );
+    assert_eq!(
+        evaluate("PHP_INT_MIN / -1", ctx),
+        Value::Float(9.223372036854776E18)
+    );
+    assert_eq!(
+        evaluate("'9223372036854775808' + 0", ctx),
+        Value::Float(9.223372036854776E18)
+    );
+    assert_eq!(
+        evaluate("['a' => 1] + ['a' => 2, 'b' => 3]", ctx),
+        Value::Array(HashMap::from([
+            ("a".into(), 1.into()),
+            ("b".into(), 3.into())
+        ]))
+    );
+    assert!(execute("<?php $a = 1 / 0;", ctx).is_err());
+    assert!(execute("<?php $a = 1 % 0;", ctx).is_err());
+    assert!(execute("<?php $a = [] * 2;", ctx).is_err());
+}
+
+#[test]
+fn test_evaluate_comparison() {
+    let ctx = Context::default();
+    assert_eq!(evaluate("1 < 2", ctx), true);
+    assert_eq!(evaluate("2 <= 2", ctx), true);
+    assert_eq!(evaluate("1.5 > 2", ctx), false);
+    assert_eq!(evaluate("3 >= 2", ctx), true);
+    assert_eq!(evaluate("'10' > '9'", ctx), true);
+    assert_eq!(evaluate("'abc' < 'abd'", ctx), true);
+    assert_eq!(evaluate("null < 1", ctx), true);
+    assert_eq!(evaluate("[1, 2] > [1]", ctx), true);
+    assert_eq!(evaluate("1 + 1 > 1 && 2 * 2 <= 4", ctx), true);
+    assert_eq!(evaluate("1 < 2 == true", ctx), true);
+    assert!(execute("<?php $a = 1 < 2 < 3;", ctx).is_err());
+}
@@ -0,0 +1,555 @@
+use super::lexer::{heredoc_body, lex, Token, TokenKind};
+use super::{EvalError, Result, SyntaxError, SyntaxErrorKind};
+use php_literal_parser::Value;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
+    Literal(Value),
+    Array(Vec<(Option<Expr>, Expr)>),
+    Variable(String),
+    /// `$base[$key]`, or `$base[]` when appending
+    Index(Box<Expr>, Option<Box<Expr>>),
+    /// Global or class constant, class constants are stored as `Class::NAME`
+    Constant(String),
+    /// `Class::$name`
+    StaticProperty(String, String),
+    Call(String, Vec<Expr>),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Cast(Cast, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Coalesce(Box<Expr>, Box<Expr>),
+    /// `condition ? then : otherwise`, `then` is omitted for `condition ?: otherwise`
+    Ternary(Box<Expr>, Option<Box<Expr>>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Cast {
+    String,
+    Int,
+    Bool,
+    Float,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BinaryOp {
+    Equal,
+    NotEqual,
+    Identical,
+    NotIdentical,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+    Concat,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+}
+
+#[derive(Debug, Clone)]
+pub enum Statement {
+    Assign {
+        target: Expr,
+        value: Expr,
+    },
+    If {
+        branches: Vec<(Expr, Block)>,
+        otherwise: Block,
+    },
+    Expression(Expr),
+}
+
+/// The body of a control structure
+#[derive(Debug, Clone, Default)]
+pub struct Block {
+    pub statements: Vec<Statement>,
+    /// A single statement without braces, like `if ($a) $b = 1;`
+    pub braceless: bool,
+}
+
+pub struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Parser {
+            source,
+            tokens: lex(source),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<TokenKind> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<TokenKind> {
+        self.tokens.get(self.pos + n).map(|token| token.kind)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(token) if token.kind == TokenKind::Ident => {
+                self.slice(token).eq_ignore_ascii_case(keyword)
+            }
+            _ => false,
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        if self.peek() == Some(kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn slice(&self, token: &Token) -> &'a str {
+        &self.source[token.span.clone()]
+    }
+
+    /// Start offset of the next token
+    fn start(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.source.len(), |token| token.span.start)
+    }
+
+    /// End offset of the last consumed token
+    fn end(&self) -> usize {
+        self.tokens
+            .get(self.pos.saturating_sub(1))
+            .map_or(0, |token| token.span.end)
+    }
+
+    fn error(&self, span: Range<usize>, kind: SyntaxErrorKind) -> EvalError {
+        SyntaxError::new(self.source, span, kind).into()
+    }
+
+    fn unexpected(&self, token: Option<Token>, expected: &'static str) -> EvalError {
+        match token {
+            Some(token) => self.error(token.span, SyntaxErrorKind::UnexpectedToken(expected)),
+            None => self.error(
+                self.source.len()..self.source.len(),
+                SyntaxErrorKind::UnexpectedEnd(expected),
+            ),
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind, expected: &'static str) -> Result<Token> {
+        match self.next() {
+            Some(token) if token.kind == kind => Ok(token),
+            token => Err(self.unexpected(token, expected)),
+        }
+    }
+
+    fn expr(&self, start: usize, kind: ExprKind) -> Expr {
+        Expr {
+            kind,
+            span: start..self.end(),
+        }
+    }
+
+    pub fn file(&mut self) -> Result<Vec<Statement>> {
+        let mut statements = Vec::new();
+        while self.peek().is_some() {
+            if !self.eat(TokenKind::SemiColon) {
+                statements.push(self.statement()?);
+            }
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement> {
+        if self.peek_keyword("if") {
+            self.pos += 1;
+            return self.if_statement();
+        }
+
+        let expr = self.expression()?;
+        let statement = if self.peek() == Some(TokenKind::Assign) {
+            if !is_assignable(&expr) {
+                return Err(self.error(
+                    expr.span,
+                    SyntaxErrorKind::UnexpectedToken("variable or array element"),
+                ));
+            }
+            self.pos += 1;
+            Statement::Assign {
+                target: expr,
+                value: self.expression()?,
+            }
+        } else {
+            Statement::Expression(expr)
+        };
+
+        match self.next() {
+            None => Ok(statement),
+            Some(token) if token.kind == TokenKind::SemiColon => Ok(statement),
+            token => Err(self.unexpected(token, "';'")),
+        }
+    }
+
+    /// Parse the remainder of an `if` statement, the `if` keyword has already been consumed
+    fn if_statement(&mut self) -> Result<Statement> {
+        let mut branches = vec![self.condition_and_block()?];
+        let mut otherwise = Block::default();
+        loop {
+            if self.peek_keyword("elseif") {
+                self.pos += 1;
+                branches.push(self.condition_and_block()?);
+            } else if self.peek_keyword("else") {
+                self.pos += 1;
+                if self.peek_keyword("if") {
+                    self.pos += 1;
+                    branches.push(self.condition_and_block()?);
+                } else {
+                    otherwise = self.block()?;
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+        Ok(Statement::If {
+            branches,
+            otherwise,
+        })
+    }
+
+    fn condition_and_block(&mut self) -> Result<(Expr, Block)> {
+        self.expect(TokenKind::BracketOpen, "'('")?;
+        let condition = self.expression()?;
+        self.expect(TokenKind::BracketClose, "')'")?;
+        Ok((condition, self.block()?))
+    }
+
+    fn block(&mut self) -> Result<Block> {
+        if !self.eat(TokenKind::CurlyOpen) {
+            return Ok(Block {
+                statements: vec![self.statement()?],
+                braceless: true,
+            });
+        }
+        let mut statements = Vec::new();
+        loop {
+            match self.peek() {
+                Some(TokenKind::CurlyClose) => {
+                    self.pos += 1;
+                    return Ok(Block {
+                        statements,
+                        braceless: false,
+                    });
+                }
+                Some(TokenKind::SemiColon) => self.pos += 1,
+                Some(_) => statements.push(self.statement()?),
+                None => return Err(self.unexpected(None, "'}'")),
+            }
+        }
+    }
+
+    pub fn expression(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let condition = self.coalesce()?;
+        if self.eat(TokenKind::Question) {
+            let then = if self.eat(TokenKind::Colon) {
+                None
+            } else {
+                let then = self.expression()?;
+                self.expect(TokenKind::Colon, "':'")?;
+                Some(Box::new(then))
+            };
+            let otherwise = self.expression()?;
+            return Ok(self.expr(
+                start,
+                ExprKind::Ternary(Box::new(condition), then, Box::new(otherwise)),
+            ));
+        }
+        Ok(condition)
+    }
+
+    fn coalesce(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let value = self.or()?;
+        if self.eat(TokenKind::Coalesce) {
+            let otherwise = self.coalesce()?;
+            return Ok(self.expr(
+                start,
+                ExprKind::Coalesce(Box::new(value), Box::new(otherwise)),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        self.binary(&[(TokenKind::Or, BinaryOp::Or)], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        self.binary(&[(TokenKind::And, BinaryOp::And)], Self::equality)
+    }
+
+    fn equality(&mut self) -> Result<Expr> {
+        self.binary(
+            &[
+                (TokenKind::Equal, BinaryOp::Equal),
+                (TokenKind::NotEqual, BinaryOp::NotEqual),
+                (TokenKind::Identical, BinaryOp::Identical),
+                (TokenKind::NotIdentical, BinaryOp::NotIdentical),
+            ],
+            Self::comparison,
+        )
+    }
+
+    /// Comparisons are non-associative, `a < b < c` is a syntax error
+    fn comparison(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let left = self.concat()?;
+        let op = match self.peek() {
+            Some(TokenKind::Less) => BinaryOp::Less,
+            Some(TokenKind::LessEqual) => BinaryOp::LessEqual,
+            Some(TokenKind::Greater) => BinaryOp::Greater,
+            Some(TokenKind::GreaterEqual) => BinaryOp::GreaterEqual,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.concat()?;
+        Ok(self.expr(start, ExprKind::Binary(op, Box::new(left), Box::new(right))))
+    }
+
+    fn concat(&mut self) -> Result<Expr> {
+        self.binary(&[(TokenKind::Dot, BinaryOp::Concat)], Self::additive)
+    }
+
+    fn additive(&mut self) -> Result<Expr> {
+        self.binary(
+            &[
+                (TokenKind::Plus, BinaryOp::Add),
+                (TokenKind::Minus, BinaryOp::Subtract),
+            ],
+            Self::multiplicative,
+        )
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr> {
+        self.binary(
+            &[
+                (TokenKind::Star, BinaryOp::Multiply),
+                (TokenKind::Slash, BinaryOp::Divide),
+                (TokenKind::Percent, BinaryOp::Modulo),
+            ],
+            Self::unary,
+        )
+    }
+
+    /// Parse a left associative binary expression
+    fn binary(
+        &mut self,
+        operators: &[(TokenKind, BinaryOp)],
+        operand: fn(&mut Self) -> Result<Expr>,
+    ) -> Result<Expr> {
+        let start = self.start();
+        let mut left = operand(self)?;
+        while let Some((_, op)) = operators
+            .iter()
+            .find(|(kind, _)| self.peek() == Some(*kind))
+        {
+            self.pos += 1;
+            let right = operand(self)?;
+            left = self.expr(
+                start,
+                ExprKind::Binary(*op, Box::new(left), Box::new(right)),
+            );
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let kind = match self.peek() {
+            Some(TokenKind::Not) => {
+                self.pos += 1;
+                ExprKind::Not(Box::new(self.unary()?))
+            }
+            Some(TokenKind::Minus) => {
+                self.pos += 1;
+                ExprKind::Negate(Box::new(self.unary()?))
+            }
+            Some(TokenKind::Plus) => {
+                self.pos += 1;
+                return self.unary();
+            }
+            Some(TokenKind::BracketOpen)
+                if self.peek_nth(1) == Some(TokenKind::Ident)
+                    && self.peek_nth(2) == Some(TokenKind::BracketClose) =>
+            {
+                let cast = match self
+                    .slice(&self.tokens[self.pos + 1])
+                    .to_ascii_lowercase()
+                    .as_str()
+                {
+                    "string" => Cast::String,
+                    "int" | "integer" => Cast::Int,
+                    "bool" | "boolean" => Cast::Bool,
+                    "float" | "double" => Cast::Float,
+                    _ => return self.postfix(),
+                };
+                self.pos += 3;
+                ExprKind::Cast(cast, Box::new(self.unary()?))
+            }
+            _ => return self.postfix(),
+        };
+        Ok(self.expr(start, kind))
+    }
+
+    fn postfix(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let mut expr = self.primary()?;
+        while self.eat(TokenKind::SquareOpen) {
+            let key = if self.eat(TokenKind::SquareClose) {
+                None
+            } else {
+                let key = self.expression()?;
+                self.expect(TokenKind::SquareClose, "']'")?;
+                Some(Box::new(key))
+            };
+            expr = self.expr(start, ExprKind::Index(Box::new(expr), key));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let token = self.next();
+        let kind = match token.as_ref().map(|token| token.kind) {
+            Some(TokenKind::String | TokenKind::Number) => {
+                let token = token.unwrap();
+                ExprKind::Literal(php_literal_parser::from_str(self.slice(&token))?)
+            }
+            Some(TokenKind::Heredoc) => {
+                let (body, nowdoc) = heredoc_body(self.slice(&token.unwrap()));
+                if nowdoc {
+                    ExprKind::Literal(Value::String(body))
+                } else {
+                    ExprKind::Literal(php_literal_parser::from_str(&double_quoted(&body))?)
+                }
+            }
+            Some(TokenKind::Variable) => {
+                ExprKind::Variable(self.slice(&token.unwrap())[1..].to_string())
+            }
+            Some(TokenKind::SquareOpen) => self.array(TokenKind::SquareClose, "',' or ']'")?,
+            Some(TokenKind::BracketOpen) => {
+                let expr = self.expression()?;
+                self.expect(TokenKind::BracketClose, "')'")?;
+                return Ok(expr);
+            }
+            Some(TokenKind::Ident) => {
+                let token = token.unwrap();
+                let name = self.slice(&token);
+                if self.eat(TokenKind::DoubleColon) {
+                    if self.peek() == Some(TokenKind::Variable) {
+                        let property = self.next().unwrap();
+                        ExprKind::StaticProperty(name.into(), self.slice(&property)[1..].into())
+                    } else {
+                        let constant = self.expect(TokenKind::Ident, "class constant")?;
+                        ExprKind::Constant(format!("{}::{}", name, self.slice(&constant)))
+                    }
+                } else if self.eat(TokenKind::BracketOpen) {
+                    if name.eq_ignore_ascii_case("array") {
+                        self.array(TokenKind::BracketClose, "',' or ')'")?
+                    } else {
+                        ExprKind::Call(name.into(), self.arguments()?)
+                    }
+                } else {
+                    ExprKind::Constant(name.into())
+                }
+            }
+            _ => return Err(self.unexpected(token, "value")),
+        };
+        Ok(self.expr(start, kind))
+    }
+
+    /// Parse the arguments of a function call, the opening bracket has already been consumed
+    fn arguments(&mut self) -> Result<Vec<Expr>> {
+        let mut args = Vec::new();
+        while !self.eat(TokenKind::BracketClose) {
+            args.push(self.expression()?);
+            if !self.eat(TokenKind::Comma) {
+                self.expect(TokenKind::BracketClose, "',' or ')'")?;
+                break;
+            }
+        }
+        Ok(args)
+    }
+
+    /// Parse an array literal, the opening bracket has already been consumed
+    fn array(&mut self, close: TokenKind, expected: &'static str) -> Result<ExprKind> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            let value = self.expression()?;
+            if self.eat(TokenKind::Arrow) {
+                items.push((Some(value), self.expression()?));
+            } else {
+                items.push((None, value));
+            }
+            if !self.eat(TokenKind::Comma) {
+                self.expect(close, expected)?;
+                break;
+            }
+        }
+        Ok(ExprKind::Array(items))
+    }
+}
+
+/// Quote the body of a heredoc as double-quoted string, heredocs use the same escape sequences
+/// but don't need double quotes to be escaped
+fn double_quoted(body: &str) -> String {
+    let mut quoted = String::with_capacity(body.len() + 2);
+    quoted.push('"');
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next) => {
+                    quoted.push(c);
+                    quoted.push(next);
+                }
+                None => quoted.push_str("\\\\"),
+            },
+            '"' => quoted.push_str("\\\""),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_assignable(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Variable(_) => true,
+        ExprKind::Index(base, _) => is_assignable(base),
+        _ => false,
+    }
+}
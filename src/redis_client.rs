@@ -0,0 +1,129 @@
+use crate::{
+    Error, FailoverMode, RedisClusterConnectionInfo, RedisConnectionAddr, RedisConnectionInfo,
+    RedisTlsParams, Result,
+};
+use redis::cluster::{ClusterClient, ClusterClientBuilder, TlsMode};
+use redis::{Client, ClientTlsConfig, ConnectionAddr, ConnectionInfo, TlsCertificates};
+use std::fs;
+use std::path::Path;
+
+impl RedisTlsParams {
+    /// Load the certificates used for the connection from disk
+    ///
+    /// The client certificate is only used if both `local_cert` and `local_pk` are set.
+    /// Returns `None` if no certificates are configured, in which case the system root certificates are used.
+    pub fn certificates(&self) -> Result<Option<TlsCertificates>> {
+        let client_tls = match (&self.local_cert, &self.local_pk) {
+            (Some(cert), Some(key)) => Some(ClientTlsConfig {
+                client_cert: read_certificate(cert)?,
+                client_key: read_certificate(key)?,
+            }),
+            _ => None,
+        };
+        let root_cert = self.ca_file.as_deref().map(read_certificate).transpose()?;
+        if client_tls.is_none() && root_cert.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(TlsCertificates {
+            client_tls,
+            root_cert,
+        }))
+    }
+}
+
+fn read_certificate(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+    fs::read(path).map_err(|err| Error::ReadCertificate(err, path.into()))
+}
+
+/// Convert the connection info without loading any certificates
+fn connection_info(info: &RedisConnectionInfo) -> ConnectionInfo {
+    let addr = match &info.addr {
+        RedisConnectionAddr::Tcp {
+            host,
+            port,
+            tls: false,
+        } => ConnectionAddr::Tcp(host.clone(), *port),
+        RedisConnectionAddr::Tcp {
+            host,
+            port,
+            tls: true,
+        } => ConnectionAddr::TcpTls {
+            host: host.clone(),
+            port: *port,
+            insecure: info.tls_params.as_ref().is_some_and(|tls| tls.insecure),
+            tls_params: None,
+        },
+        RedisConnectionAddr::Unix { path } => ConnectionAddr::Unix(path.clone()),
+    };
+    ConnectionInfo {
+        addr,
+        redis: redis::RedisConnectionInfo {
+            db: info.db,
+            username: info.username.clone(),
+            password: info.password.clone(),
+            ..Default::default()
+        },
+    }
+}
+
+impl TryFrom<&RedisConnectionInfo> for ConnectionInfo {
+    type Error = Error;
+
+    /// Convert the connection info, loading the configured tls certificates from disk
+    ///
+    /// `verify_peer_name` can't be disabled separately for single connections,
+    /// only disabling `verify_peer` is supported.
+    fn try_from(info: &RedisConnectionInfo) -> Result<Self> {
+        let connection = connection_info(info);
+        let certificates = match (&info.addr, &info.tls_params) {
+            (RedisConnectionAddr::Tcp { tls: true, .. }, Some(tls)) => tls.certificates()?,
+            _ => None,
+        };
+        match certificates {
+            Some(certificates) => Ok(Client::build_with_tls(connection, certificates)?
+                .get_connection_info()
+                .clone()),
+            None => Ok(connection),
+        }
+    }
+}
+
+impl RedisClusterConnectionInfo {
+    /// Create a client for the cluster, loading the configured tls certificates from disk
+    ///
+    /// Reads are sent to replicas if the failover mode distributes them.
+    pub fn cluster_client(&self) -> Result<ClusterClient> {
+        let nodes = self.iter().map(|node| connection_info(&node));
+        let mut builder = ClusterClientBuilder::new(nodes);
+        let uses_tls = self
+            .addr
+            .iter()
+            .any(|addr| matches!(addr, RedisConnectionAddr::Tcp { tls: true, .. }));
+        if let Some(tls) = self.tls_params.as_ref().filter(|_| uses_tls) {
+            // set explicitly since adding certificates would otherwise enable certificate verification
+            builder = builder.tls(if tls.insecure {
+                TlsMode::Insecure
+            } else {
+                TlsMode::Secure
+            });
+            if let Some(certificates) = tls.certificates()? {
+                builder = builder.certs(certificates);
+            }
+            builder = builder.danger_accept_invalid_hostnames(tls.accept_invalid_hostname);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.connection_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.response_timeout(timeout);
+        }
+        if matches!(
+            self.failover_mode,
+            FailoverMode::Distribute | FailoverMode::DistributeSlaves
+        ) {
+            builder = builder.read_from_replicas();
+        }
+        Ok(builder.build()?)
+    }
+}
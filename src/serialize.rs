@@ -0,0 +1,200 @@
+use crate::constants::CONFIG_CONSTANTS;
+use crate::php::format_float;
+use php_literal_parser::{Key, Value};
+use std::fmt::Write;
+
+/// Integer config values and array keys that are written as constants
+enum ConstantRule {
+    /// The integer keys of the array at `array`
+    Key {
+        array: &'static [&'static str],
+        prefixes: &'static [&'static str],
+    },
+    /// The integer value at `path`
+    Value {
+        path: &'static [&'static str],
+        prefixes: &'static [&'static str],
+    },
+}
+
+static CONSTANT_RULES: &[ConstantRule] = &[
+    ConstantRule::Key {
+        array: &["dbdriveroptions"],
+        prefixes: &[r"\PDO::ATTR_", r"\PDO::MYSQL_ATTR_"],
+    },
+    ConstantRule::Key {
+        array: &["memcached_options"],
+        prefixes: &[r"\Memcached::OPT_"],
+    },
+    ConstantRule::Value {
+        path: &["memcached_options", r"\Memcached::OPT_SERIALIZER"],
+        prefixes: &[r"\Memcached::SERIALIZER_"],
+    },
+    ConstantRule::Value {
+        path: &["memcached_options", r"\Memcached::OPT_HASH"],
+        prefixes: &[r"\Memcached::HASH_"],
+    },
+    ConstantRule::Value {
+        path: &["memcached_options", r"\Memcached::OPT_DISTRIBUTION"],
+        prefixes: &[r"\Memcached::DISTRIBUTION_"],
+    },
+    ConstantRule::Value {
+        path: &["redis.cluster", "failover_mode"],
+        prefixes: &[r"\RedisCluster::FAILOVER_"],
+    },
+];
+
+/// Write a config value as a php config file, formatted like nextcloud writes its `config.php`
+///
+/// Array keys are written in sorted order and integer options like `dbdriveroptions` and
+/// `memcached_options` are written using the constants nextcloud's documentation uses for them.
+pub fn to_php(config: &Value) -> String {
+    let mut out = String::from("<?php\n$CONFIG = ");
+    write_value(&mut out, config, &mut Vec::new(), 0);
+    out.push_str(";\n");
+    out
+}
+
+/// Write a value, `path` contains the keys leading up to the value
+fn write_value(out: &mut String, value: &Value, path: &mut Vec<String>, indent: usize) {
+    match value {
+        Value::Null => out.push_str("NULL"),
+        Value::Bool(true) => out.push_str("true"),
+        Value::Bool(false) => out.push_str("false"),
+        Value::Int(int) => match value_constant(path, *int) {
+            Some(constant) => out.push_str(constant),
+            None => write_int(out, *int),
+        },
+        Value::Float(float) => write_float(out, *float),
+        Value::String(str) => write_string(out, str),
+        Value::Array(map) => {
+            let mut items = map.iter().collect::<Vec<_>>();
+            items.sort_by_key(|(key, _)| *key);
+
+            out.push_str("array (\n");
+            for (key, item) in items {
+                push_indent(out, indent + 2);
+                write_item(out, key, item, path, indent + 2);
+                out.push_str(",\n");
+            }
+            push_indent(out, indent);
+            out.push(')');
+        }
+    }
+}
+
+/// Write a `key => value` pair, `path` contains the keys leading up to the array containing the item
+fn write_item(out: &mut String, key: &Key, value: &Value, path: &mut Vec<String>, indent: usize) {
+    let name = match key {
+        Key::Int(int) => match key_constant(path, *int) {
+            Some(constant) => {
+                out.push_str(constant);
+                constant.to_string()
+            }
+            None => {
+                write_int(out, *int);
+                int.to_string()
+            }
+        },
+        Key::String(str) => {
+            write_string(out, str);
+            str.clone()
+        }
+    };
+    out.push_str(" => ");
+    if value.is_array() {
+        // like php's `var_export`, nested arrays start on their own line
+        out.push('\n');
+        push_indent(out, indent);
+    }
+    path.push(name);
+    write_value(out, value, path, indent);
+    path.pop();
+}
+
+/// Format a top-level config item as `'key' => value`, for an item indented by `indent`
+pub(crate) fn item_to_php(key: &str, value: &Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_item(&mut out, &Key::from(key), value, &mut Vec::new(), indent);
+    out
+}
+
+/// Format the value of a top-level config item, for an item indented by `indent`
+pub(crate) fn value_to_php(key: &str, value: &Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, &mut vec![key.to_string()], indent);
+    out
+}
+
+fn key_constant(array: &[String], value: i64) -> Option<&'static str> {
+    CONSTANT_RULES.iter().find_map(|rule| match rule {
+        ConstantRule::Key {
+            array: rule_array,
+            prefixes,
+        } if *rule_array == array => find_constant(prefixes, value),
+        _ => None,
+    })
+}
+
+fn value_constant(path: &[String], value: i64) -> Option<&'static str> {
+    CONSTANT_RULES.iter().find_map(|rule| match rule {
+        ConstantRule::Value {
+            path: rule_path,
+            prefixes,
+        } if *rule_path == path => find_constant(prefixes, value),
+        _ => None,
+    })
+}
+
+fn find_constant(prefixes: &[&str], value: i64) -> Option<&'static str> {
+    CONFIG_CONSTANTS
+        .iter()
+        .find(|(name, constant)| {
+            *constant == value && prefixes.iter().any(|prefix| name.starts_with(prefix))
+        })
+        .map(|(name, _)| *name)
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    write!(out, "{:indent$}", "").unwrap();
+}
+
+fn write_int(out: &mut String, int: i64) {
+    if int == i64::MIN {
+        // the literal for `i64::MIN` would overflow into a float in php
+        out.push_str("PHP_INT_MIN");
+    } else {
+        write!(out, "{}", int).unwrap();
+    }
+}
+
+fn write_float(out: &mut String, float: f64) {
+    let formatted = format_float(float, None);
+    out.push_str(&formatted);
+    // like `var_export`, keep floats without fraction recognizable as float
+    if float.is_finite() && !formatted.contains(['.', 'E']) {
+        out.push_str(".0");
+    }
+}
+
+/// Format a string as a single-quoted php string
+pub(crate) fn string_to_php(str: &str) -> String {
+    let mut out = String::new();
+    write_string(&mut out, str);
+    out
+}
+
+/// Write a single-quoted php string
+fn write_string(out: &mut String, str: &str) {
+    out.push('\'');
+    for c in str.chars() {
+        match c {
+            '\\' => out.push_str(r"\\"),
+            '\'' => out.push_str(r"\'"),
+            // null bytes can't be written in a single-quoted string
+            '\0' => out.push_str(r#"' . "\0" . '"#),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+}
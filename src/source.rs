@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where config files are loaded from
+///
+/// The default source is the local filesystem, implement this trait to load configs from elsewhere,
+/// like a remote server or a container image.
+pub trait ConfigSource: Debug + Send + Sync {
+    /// List the paths of all files in a directory
+    fn list_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Read the contents of a file
+    fn read_file(&self, path: &Path) -> io::Result<String>;
+}
+
+/// Load config files from the local filesystem
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSystemSource;
+
+impl ConfigSource for FileSystemSource {
+    fn list_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        dir.read_dir()?.map(|entry| Ok(entry?.path())).collect()
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// In-memory config files, keyed by path
+impl ConfigSource for HashMap<PathBuf, String> {
+    fn list_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .keys()
+            .filter(|path| path.parent() == Some(dir))
+            .cloned()
+            .collect())
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
@@ -0,0 +1,119 @@
+use crate::php::{self, Context};
+use crate::source::ConfigSource;
+use crate::{Error, PhpParseError, Result};
+use php_literal_parser::Value;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A dotted nextcloud version number like `30.0.2.1`
+#[derive(Debug, Clone)]
+pub struct Version(pub Vec<u32>);
+
+impl Version {
+    pub fn major(&self) -> u32 {
+        self.0.first().copied().unwrap_or_default()
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Versions are compared part by part, missing parts are treated as `0`
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let len = self.0.len().max(other.0.len());
+        let part = |version: &Version, i: usize| version.0.get(i).copied().unwrap_or_default();
+        (0..len)
+            .map(|i| part(self, i).cmp(&part(other, i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+impl FromStr for Version {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .split('.')
+            .map(u32::from_str)
+            .collect::<Result<_, _>>()
+            .map(Version)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, part) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", part)?;
+        }
+        Ok(())
+    }
+}
+
+/// The contents of nextcloud's `version.php`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// `$OC_Version`
+    pub version: Version,
+    /// `$OC_VersionString`, the human-readable version like `30.0.2`
+    pub version_string: String,
+    /// `$OC_Channel`, the release channel like `stable` or `beta`
+    pub channel: String,
+}
+
+pub(crate) fn parse_version_file(
+    path: &Path,
+    source: &dyn ConfigSource,
+    ctx: Context,
+) -> Result<VersionInfo> {
+    let content = source
+        .read_file(path)
+        .map_err(|err| Error::ReadFailed(err, path.into()))?;
+    let mut output = php::execute(&content, ctx).map_err(|err| {
+        Error::Php(PhpParseError {
+            err,
+            path: path.into(),
+        })
+    })?;
+    let mut variable = |name: &str| output.variables.remove(name).unwrap_or(Value::Null);
+
+    let version = match variable("OC_Version") {
+        Value::Array(parts) => {
+            let mut parts = parts.into_iter().collect::<Vec<_>>();
+            parts.sort_by(|(a, _), (b, _)| a.cmp(b));
+            parts
+                .into_iter()
+                .map(|(_, part)| part.as_int().and_then(|part| u32::try_from(part).ok()))
+                .collect::<Option<Vec<_>>>()
+                .map(Version)
+        }
+        _ => None,
+    }
+    .ok_or_else(|| Error::InvalidVersionFile(path.into()))?;
+    let version_string = variable("OC_VersionString")
+        .into_string()
+        .unwrap_or_else(|| version.to_string());
+    let channel = variable("OC_Channel").into_string().unwrap_or_default();
+
+    Ok(VersionInfo {
+        version,
+        version_string,
+        channel,
+    })
+}
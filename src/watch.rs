@@ -0,0 +1,106 @@
+use crate::nc::is_extra_config_file;
+use crate::{Config, ConfigChange, ConfigParser, Error, Result};
+use inotify::{Inotify, WatchMask};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// Something that happened to the watched config files
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// The config files were changed and parsed successfully
+    Changed(Vec<ConfigChange>),
+    /// The config files were changed but failed to parse, the last good config is kept
+    Invalid(Error),
+}
+
+/// Re-parses the config whenever the config file or any `*.config.php` next to it changes
+///
+/// Changes are detected with inotify, so only configs on the local filesystem can be watched.
+pub struct ConfigWatcher {
+    parser: ConfigParser,
+    path: PathBuf,
+    /// The watched directory containing the config files
+    dir: PathBuf,
+    config: Config,
+    inotify: Inotify,
+    buffer: Vec<u8>,
+}
+
+impl ConfigWatcher {
+    pub(crate) fn new(parser: ConfigParser, path: &Path) -> Result<Self> {
+        let config = parser.parse_glob(path)?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let watch_error = |err| Error::Watch(err, dir.into());
+        let inotify = Inotify::init().map_err(watch_error)?;
+        // watching the directory instead of the files lets us see new config files
+        // and files that are replaced by moving a new version into place
+        inotify
+            .watches()
+            .add(
+                dir,
+                WatchMask::CLOSE_WRITE
+                    | WatchMask::MOVED_TO
+                    | WatchMask::MOVED_FROM
+                    | WatchMask::DELETE,
+            )
+            .map_err(watch_error)?;
+
+        Ok(ConfigWatcher {
+            parser,
+            path: path.into(),
+            dir: dir.into(),
+            config,
+            inotify,
+            buffer: vec![0; 4096],
+        })
+    }
+
+    /// The last config that was parsed successfully
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Block until the config changes
+    ///
+    /// Changes to the files that don't change the parsed config are ignored.
+    pub fn wait(&mut self) -> Result<WatchEvent> {
+        loop {
+            let events = self
+                .inotify
+                .read_events_blocking(&mut self.buffer)
+                .map_err(|err| Error::Watch(err, self.dir.clone()))?;
+            let names: Vec<_> = events
+                .filter_map(|event| event.name.map(OsStr::to_os_string))
+                .collect();
+            if !names.iter().any(|name| self.is_config_file(name)) {
+                continue;
+            }
+
+            match self.parser.parse_glob(&self.path) {
+                Ok(config) => {
+                    let changes = config.changes_since(&self.config);
+                    self.config = config;
+                    if !changes.is_empty() {
+                        return Ok(WatchEvent::Changed(changes));
+                    }
+                }
+                Err(err) => return Ok(WatchEvent::Invalid(err)),
+            }
+        }
+    }
+
+    /// Check if a file in the watched directory is the main config file or an extra config file
+    fn is_config_file(&self, name: &OsStr) -> bool {
+        Some(name) == self.path.file_name() || name.to_str().is_some_and(is_extra_config_file)
+    }
+}
+
+impl ConfigParser {
+    /// Parse a config file and all `*.config.php` files next to it and watch them for changes
+    pub fn watch(&self, path: impl AsRef<Path>) -> Result<ConfigWatcher> {
+        ConfigWatcher::new(self.clone(), path.as_ref())
+    }
+}
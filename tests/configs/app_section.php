@@ -0,0 +1,21 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'myapp' => [
+		'name' => 'My App',
+		'enabled' => true,
+		'servers' => [
+			['host' => 'a.example.com', 'port' => 8080],
+			['host' => 'b.example.com'],
+		],
+		'mode' => 'fast',
+	],
+	'otherapp' => [
+		'servers' => [
+			['host' => 'a.example.com', 'port' => 'http'],
+		],
+	],
+];
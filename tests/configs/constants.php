@@ -0,0 +1,23 @@
+<?php
+$CONFIG = [
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'dbtype' => 'mysql',
+	'dbname' => 'nextcloud',
+	'dbhost' => '127.0.0.1',
+	'dbuser' => 'nextcloud',
+	'dbpassword' => 'secret',
+	'dbdriveroptions' => [
+		\PDO::MYSQL_ATTR_INIT_COMMAND => 'SET wait_timeout = 28800',
+	],
+	'loglevel' => \OCP\ILogger::WARN,
+	'memcache.local' => \OC\Memcache\APCu::class,
+	'objectstore' => [
+		'class' => \OC\Files\ObjectStore\S3::class,
+	],
+	'redis' => [
+		'host' => 'localhost',
+		'serializer' => \Redis::SERIALIZER_IGBINARY,
+	],
+	'filesystem_check_changes' => PHP_INT_MAX,
+	'myapp.mode' => \MyApp\Mode::FAST,
+];
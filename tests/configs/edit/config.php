@@ -0,0 +1,17 @@
+<?php
+// managed by provisioning
+$CONFIG = array (
+  'instanceid' => 'oc1234', // do not change
+  'dbtype' => 'mysql',
+  'dbhost' => 'db',
+  'dbname' => 'nextcloud',
+  'dbuser' => 'nextcloud',
+  'dbpassword' => 'old-secret',
+  'overwrite.cli.url' => 'https://cloud.example.com',
+  'trusted_domains' =>
+  array (
+    0 => 'cloud.example.com',
+  ),
+  'loglevel' => 2
+);
+$CONFIG['maintenance'] = false;
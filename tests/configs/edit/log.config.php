@@ -0,0 +1,5 @@
+<?php
+$CONFIG = [
+  /* verbose logging for debugging */
+  'loglevel' => 0,
+];
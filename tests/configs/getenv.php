@@ -0,0 +1,15 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => getenv('OVERWRITE_CLI_URL') ?: 'https://cloud.example.com',
+	'dbtype' => 'pgsql',
+	'dbname' => getenv('POSTGRES_DB'),
+	'dbhost' => (string) getenv('POSTGRES_HOST'),
+	'dbuser' => getenv('POSTGRES_USER'),
+	'dbpassword' => getenv('POSTGRES_PASSWORD') ?: '',
+	'redis' => [
+		'host' => getenv('REDIS_HOST'),
+		'password' => (string) getenv('REDIS_HOST_PASSWORD'),
+		'port' => (int) getenv('REDIS_HOST_PORT') ?: 6379,
+	],
+];
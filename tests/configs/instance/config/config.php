@@ -0,0 +1,15 @@
+<?php
+$CONFIG = array (
+  'instanceid' => 'oc1234567890',
+  'passwordsalt' => 'salt',
+  'secret' => 'secret',
+  'trusted_domains' => 
+  array (
+    0 => 'cloud.example.com',
+  ),
+  'datadirectory' => OC::$SERVERROOT . '/data',
+  'dbtype' => 'sqlite3',
+  'version' => '30.0.2.1',
+  'overwrite.cli.url' => 'https://cloud.example.com',
+  'installed' => true,
+);
@@ -0,0 +1,4 @@
+<?php
+$CONFIG = array (
+  'maintenance_window_start' => 1,
+);
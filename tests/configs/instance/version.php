@@ -0,0 +1,18 @@
+<?php 
+$OC_Version = array(30,0,2,1);
+$OC_VersionString = '30.0.2';
+$OC_Edition = '';
+$OC_Channel = 'stable';
+$OC_VersionCanBeUpgradedFrom = array (
+  'nextcloud' => 
+  array (
+    '29.0' => true,
+    '30.0' => true,
+  ),
+  'owncloud' => 
+  array (
+    '10.13' => true,
+  ),
+);
+$OC_Build = '2024-11-14T15:44:41+00:00 3fa3bc3b6cb1a4cd7b7a4e2fcf41ec7be9f47ae0';
+$vendor = 'nextcloud';
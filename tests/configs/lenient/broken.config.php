@@ -0,0 +1,4 @@
+<?php
+
+$CONFIG = [
+	'memcache.local' => '\OC\Memcache\APCu',
@@ -0,0 +1,11 @@
+<?php
+
+$CONFIG = [
+	'dbtype' => 'oci',
+	'dbname' => 'nextcloud',
+	'dbhost' => '127.0.0.1',
+	'redis' => [
+		'host' => 'redis',
+		'port' => 70000,
+	],
+];
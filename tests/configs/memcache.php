@@ -0,0 +1,8 @@
+<?php
+$CONFIG = [
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'memcache.local' => '\OC\Memcache\APCu',
+	'memcache.locking' => 'OC\Memcache\Redis',
+];
@@ -0,0 +1,5 @@
+<?php
+$CONFIG = [
+	'memcache.local' => '\OC\Memcache\Unknown',
+	'memcache.distributed' => '\OC\Memcache\Memcached',
+];
@@ -0,0 +1,20 @@
+<?php
+$CONFIG = [
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'memcache.distributed' => '\OC\Memcache\Memcached',
+	'memcached_servers' => [
+		['localhost', 11211],
+		['memcached.example.com', '11212', 2],
+		['/var/run/memcached/memcached.sock', 0],
+		['memcached:11213'],
+	],
+	'memcached_options' => [
+		\Memcached::OPT_CONNECT_TIMEOUT => 50,
+		\Memcached::OPT_BINARY_PROTOCOL => true,
+		\Memcached::OPT_SERIALIZER => \Memcached::SERIALIZER_IGBINARY,
+		\Memcached::OPT_PREFIX_KEY => 'nc_',
+		9999 => 1,
+	],
+];
@@ -0,0 +1,2 @@
+<?php
+$CONFIG = 'not an array';
@@ -0,0 +1,5 @@
+<?php
+// ignored by nextcloud since php's glob doesn't match hidden files
+$CONFIG = [
+	'overwrite.cli.url' => 'https://hidden.example.com',
+];
@@ -0,0 +1,5 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://a.example.com',
+];
@@ -0,0 +1,5 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://b2.example.com',
+];
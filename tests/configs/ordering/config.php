@@ -0,0 +1,7 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://config.example.com',
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/nc',
+];
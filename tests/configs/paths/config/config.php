@@ -0,0 +1,18 @@
+<?php
+$CONFIG = array (
+  'overwrite.cli.url' => 'https://cloud.example.com',
+  'dbtype' => 'sqlite3',
+  'datadirectory' => __DIR__ . '/../data',
+  'apps_paths' => array (
+    0 => array (
+      'path' => OC::$SERVERROOT . '/apps',
+      'url' => '/apps',
+      'writable' => false,
+    ),
+    1 => array (
+      'path' => \OC::$SERVERROOT . '/custom_apps',
+      'url' => '/custom_apps',
+      'writable' => true,
+    ),
+  ),
+);
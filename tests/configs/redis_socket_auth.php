@@ -0,0 +1,12 @@
+<?php
+$CONFIG = [
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'redis' => [
+		'host' => '/run/redis/redis server.sock',
+		'port' => 0,
+		'password' => 'se&cret',
+		'dbindex' => 1,
+	],
+];
@@ -0,0 +1,12 @@
+<?php
+$CONFIG = [
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'redis' => [
+		'host' => 'redis',
+		'timeout' => 1.5,
+		'read_timeout' => '2',
+	],
+	'redis.persistent' => false,
+];
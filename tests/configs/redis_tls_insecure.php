@@ -0,0 +1,17 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'dbtype' => 'mysql',
+	'dbname' => 'nextcloud',
+	'dbhost' => 'localhost',
+	'dbport' => '',
+	'dbuser' => 'nextcloud',
+	'dbpassword' => 'secret',
+	'redis' => [
+		'host' => 'tls://localhost',
+		'ssl_context' => [
+			'verify_peer' => false,
+		],
+	],
+];
@@ -0,0 +1,16 @@
+<?php
+$CONFIG = [
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'redis' => [
+		'host' => 'tls://redis.example.com',
+		'port' => 6380,
+		'user' => 'nc user',
+		'password' => 'p@ss/w:rd#',
+		'dbindex' => 2,
+		'ssl_context' => [
+			'verify_peer' => false,
+		],
+	],
+];
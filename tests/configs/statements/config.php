@@ -0,0 +1,13 @@
+<?php
+$CONFIG = array (
+  'overwrite.cli.url' => 'https://cloud.example.com',
+  'dbtype' => 'mysql',
+  'dbname' => 'nextcloud',
+  'dbhost' => '127.0.0.1',
+  'dbport' => '',
+);
+$CONFIG = array_merge($CONFIG, [
+  'dbtableprefix' => 'nc_',
+  'dbuser' => 'nextcloud',
+]);
+$CONFIG['dbpassword'] = 'secret';
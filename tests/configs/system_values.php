@@ -0,0 +1,19 @@
+<?php
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'dbtype' => 'sqlite3',
+	'datadirectory' => '/var/www/data',
+	'maintenance' => 'true',
+	'debug' => 0,
+	'loglevel' => '2',
+	'filelocking.enabled' => null,
+	'trusted_domains' => [
+		1 => 'cloud.example.com',
+		0 => 'localhost',
+		2 => 10,
+	],
+	'app_install_overwrite' => [
+		'files' => ['a'],
+	],
+];
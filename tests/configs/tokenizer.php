@@ -0,0 +1,27 @@
+<?php
+// $CONFIG is managed by ansible, don't edit by hand
+/*
+ * $CONFIG = ['dbtype' => 'pgsql'];
+ */
+# $CONFIG['dbhost'] = 'wrong';
+
+$CONFIG = [
+	'overwrite.cli.url' => 'https://cloud.example.com',
+	'dbtype' => 'mysql',
+	'dbname' => 'nextcloud',
+	'dbhost' => '127.0.0.1',
+	'dbuser' => 'nextcloud',
+	'dbpassword' => '\RedisCluster::FAILOVER_ERROR',
+	'theme' => "// not a comment",
+	'maintenance_message' => <<<EOT
+		Maintenance "in progress"
+		  see \$CONFIG
+		EOT,
+	'motd' => <<<'EOT'
+	raw \n text
+	EOT,
+	'redis.cluster' => [
+		'seeds' => ['db1:6380'],
+		'failover_mode' => \RedisCluster::FAILOVER_ERROR,
+	],
+];